@@ -0,0 +1,302 @@
+//! Noise that evolves over time.
+//!
+//! [`TimeNoise`] adds time as an extra noise dimension: 2D noise is
+//! animated through 3D noise and 3D noise through 4D noise. With a loop
+//! period, 2D noise moves along a circle through 4D noise so the animation
+//! repeats seamlessly; 3D noise cross-fades between two time offsets
+//! instead, since Perlin noise has no fifth dimension.
+
+#[cfg(feature = "image")]
+use crate::NoiseImage;
+use crate::{Noise, NoiseSampler};
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+use glam::DVec3;
+use std::f64::consts::TAU;
+
+/// Noise animated over time.
+///
+/// The noise's input transform applies to the spatial coordinates. Time
+/// is multiplied by the speed and used directly as a noise-space
+/// coordinate, so a speed of 1.0 moves about one noise feature per second.
+///
+/// As a component, the time is advanced with `Time` by `NoisePlugin`.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{Noise, TimeNoise};
+///
+/// let caustics = TimeNoise::new(Noise::new(12345).with_scale(0.1))
+///     .with_speed(0.5)
+///     .with_loop(8.0);
+///
+/// // Identical values one period apart
+/// let a = caustics.get_raw_at(3.0, 4.0, 1.0);
+/// let b = caustics.get_raw_at(3.0, 4.0, 9.0);
+/// assert!((a - b).abs() < 1e-9);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct TimeNoise {
+    noise: Noise,
+    speed: f64,
+    period: Option<f64>,
+    time: f64,
+}
+
+impl TimeNoise {
+    /// Animate `noise` at a speed of 1.0 without looping.
+    #[must_use]
+    pub fn new(noise: Noise) -> Self {
+        Self {
+            noise,
+            speed: 1.0,
+            period: None,
+            time: 0.0,
+        }
+    }
+
+    /// Set how fast the noise evolves, in noise-space units per second.
+    #[must_use]
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Repeat the animation seamlessly every `period` seconds.
+    #[must_use]
+    pub fn with_loop(mut self, period: f64) -> Self {
+        self.period = (period > 0.0).then_some(period);
+        self
+    }
+
+    /// Set the current time in seconds.
+    #[must_use]
+    pub fn with_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Get the animated noise.
+    #[must_use]
+    pub fn noise(&self) -> &Noise {
+        &self.noise
+    }
+
+    /// Get the loop period, if looping.
+    #[must_use]
+    pub fn period(&self) -> Option<f64> {
+        self.period
+    }
+
+    /// Get the current time in seconds.
+    #[must_use]
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Update the current time in seconds.
+    pub fn set_time(&mut self, time: f64) {
+        self.time = time;
+    }
+
+    /// Advance the current time by `delta` seconds. Looping noise keeps
+    /// its time within one period.
+    pub fn advance(&mut self, delta: f64) {
+        self.time += delta;
+        if let Some(period) = self.period {
+            self.time = self.time.rem_euclid(period);
+        }
+    }
+
+    /// Get raw 2D noise at the current time.
+    #[must_use]
+    pub fn get_raw(&self, x: f64, y: f64) -> f64 {
+        self.get_raw_at(x, y, self.time)
+    }
+
+    /// Get normalized 2D noise (0.0 to 1.0) at the current time.
+    #[must_use]
+    pub fn get_normalized(&self, x: f64, y: f64) -> f64 {
+        (self.get_raw(x, y) + 1.0) * 0.5
+    }
+
+    /// Get 2D noise scaled to the noise's configured range at the current
+    /// time.
+    #[must_use]
+    pub fn get_scaled(&self, x: f64, y: f64) -> f64 {
+        self.noise.map_to_range(self.get_normalized(x, y))
+    }
+
+    /// Get raw 3D noise at the current time.
+    #[must_use]
+    pub fn get_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.get_raw_3d_at(x, y, z, self.time)
+    }
+
+    /// Get normalized 3D noise (0.0 to 1.0) at the current time.
+    #[must_use]
+    pub fn get_normalized_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        (self.get_raw_3d(x, y, z) + 1.0) * 0.5
+    }
+
+    /// Get raw 2D noise at `time` seconds.
+    #[must_use]
+    pub fn get_raw_at(&self, x: f64, y: f64, time: f64) -> f64 {
+        let point = self.noise.to_noise_space(DVec3::new(x, y, 0.0));
+        match self.period {
+            Some(period) => {
+                // A circle whose circumference is the distance travelled in
+                // one period
+                let angle = time / period * TAU;
+                let radius = self.speed * period / TAU;
+                self.noise
+                    .evaluate([point.x, point.y, radius * angle.cos(), radius * angle.sin()])
+            }
+            None => self.noise.evaluate([point.x, point.y, time * self.speed]),
+        }
+    }
+
+    /// Get raw 3D noise at `time` seconds.
+    #[must_use]
+    pub fn get_raw_3d_at(&self, x: f64, y: f64, z: f64, time: f64) -> f64 {
+        let point = self.noise.to_noise_space(DVec3::new(x, y, z));
+        let at = |time: f64| {
+            self.noise
+                .evaluate([point.x, point.y, point.z, time * self.speed])
+        };
+        match self.period {
+            Some(period) => {
+                let time = time.rem_euclid(period);
+                let blend = time / period;
+                at(time) * (1.0 - blend) + at(time - period) * blend
+            }
+            None => at(time),
+        }
+    }
+}
+
+/// Samples raw noise at the current time.
+impl NoiseSampler for TimeNoise {
+    #[inline]
+    fn sample_raw(&self, x: f64, y: f64) -> f64 {
+        self.get_raw(x, y)
+    }
+
+    #[inline]
+    fn sample_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.get_raw_3d(x, y, z)
+    }
+}
+
+/// Regenerates an [`Image`] from the entity's [`TimeNoise`] every frame.
+///
+/// Pixels hold normalized noise values at the current time. They are
+/// written in place with [`NoiseImage::update_with`], so the image keeps
+/// its sampler and texture descriptor.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::math::{DVec2, UVec2};
+/// use bevy::prelude::*;
+/// use msg_noise::{Noise, NoiseImage, NoiseImageFormat, SampleRegion, TimeNoise, TimeNoiseImage};
+///
+/// fn spawn_water(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+///     let settings = NoiseImage::new(
+///         UVec2::splat(128),
+///         SampleRegion::new(DVec2::ZERO, DVec2::splat(32.0)),
+///         NoiseImageFormat::R8,
+///     );
+///     let noise = TimeNoise::new(Noise::new(12345).with_scale(0.2)).with_loop(10.0);
+///     let image = images.add(settings.build_with(|x, y| noise.get_normalized(x, y)));
+///
+///     commands.spawn((noise, TimeNoiseImage::new(image, settings)));
+/// }
+/// ```
+#[cfg(feature = "image")]
+#[derive(Component, Clone, Debug)]
+pub struct TimeNoiseImage {
+    image: Handle<Image>,
+    settings: NoiseImage,
+}
+
+#[cfg(feature = "image")]
+impl TimeNoiseImage {
+    /// Regenerate `image` with `settings` every frame.
+    #[must_use]
+    pub fn new(image: Handle<Image>, settings: NoiseImage) -> Self {
+        Self { image, settings }
+    }
+
+    /// Get the image handle.
+    #[must_use]
+    pub fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+}
+
+/// Advance every [`TimeNoise`] component.
+#[cfg(feature = "bevy")]
+pub(crate) fn advance_time_noise(time: Res<Time>, mut noises: Query<&mut TimeNoise>) {
+    let delta = time.delta_secs_f64();
+    for mut noise in &mut noises {
+        noise.advance(delta);
+    }
+}
+
+/// Regenerate images driven by [`TimeNoise`] components.
+#[cfg(feature = "image")]
+pub(crate) fn update_time_noise_images(
+    noises: Query<(&TimeNoise, &TimeNoiseImage)>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (noise, target) in &noises {
+        if let Some(image) = images.get_mut(&target.image) {
+            target
+                .settings
+                .update_with(image, |x, y| noise.get_normalized(x, y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looping_2d_repeats_each_period() {
+        let noise = TimeNoise::new(Noise::new(3).with_scale(0.1)).with_loop(5.0);
+
+        for i in 0..10 {
+            let (x, t) = (f64::from(i) * 1.3, f64::from(i) * 0.7);
+            assert!((noise.get_raw_at(x, 2.0, t) - noise.get_raw_at(x, 2.0, t + 5.0)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn looping_3d_is_continuous_across_the_wrap() {
+        let noise = TimeNoise::new(Noise::new(3).with_scale(0.1))
+            .with_speed(0.5)
+            .with_loop(4.0);
+
+        let before = noise.get_raw_3d_at(1.0, 2.0, 3.0, 4.0 - 1e-6);
+        let after = noise.get_raw_3d_at(1.0, 2.0, 3.0, 4.0 + 1e-6);
+        assert!((before - after).abs() < 1e-4);
+    }
+
+    #[test]
+    fn time_changes_values_smoothly() {
+        let mut noise = TimeNoise::new(Noise::new(3).with_scale(0.1)).with_speed(2.0);
+        let start = noise.get_raw(1.5, 2.5);
+
+        noise.advance(0.001);
+        let near = noise.get_raw(1.5, 2.5);
+        noise.advance(1.0);
+        let far = noise.get_raw(1.5, 2.5);
+
+        assert!((start - near).abs() < 0.01);
+        assert!((start - far).abs() > (start - near).abs());
+    }
+}
@@ -0,0 +1,138 @@
+//! Image output through the `image` crate.
+//!
+//! PNG and PGM store 16-bit grayscale of values normalized to 0.0 to 1.0.
+//! EXR and raw output store the values unchanged as 32-bit floats.
+
+use image::codecs::openexr::OpenExrEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::pnm::{GraymapHeader, PnmEncoder, PnmHeader, SampleEncoding};
+use image::{ExtendedColorType, ImageEncoder, ImageResult};
+use std::io::{Seek, Write};
+
+/// Output file format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// 16-bit grayscale PNG.
+    Png,
+    /// 16-bit binary PGM (P5).
+    Pgm,
+    /// 32-bit float OpenEXR, with the value in all three color channels.
+    Exr,
+    /// Little-endian `f32` values, row by row.
+    Raw,
+}
+
+impl Format {
+    /// Parse a format name or file extension.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "pgm" => Some(Self::Pgm),
+            "exr" => Some(Self::Exr),
+            "raw" | "f32" | "bin" => Some(Self::Raw),
+            _ => None,
+        }
+    }
+
+    /// Whether the format stores normalized rather than raw values.
+    pub fn is_normalized(self) -> bool {
+        matches!(self, Self::Png | Self::Pgm)
+    }
+}
+
+/// Encode `values` (`width * height`, row by row) in `format`.
+pub fn encode(
+    format: Format,
+    width: u32,
+    height: u32,
+    values: &[f32],
+    mut out: impl Write + Seek,
+) -> ImageResult<()> {
+    match format {
+        Format::Png => {
+            PngEncoder::new(out).write_image(&gray16(values), width, height, ExtendedColorType::L16)
+        }
+        Format::Pgm => {
+            // The graymap subtype alone only selects 8-bit output
+            let header = PnmHeader::from(GraymapHeader {
+                encoding: SampleEncoding::Binary,
+                width,
+                height,
+                maxwhite: u32::from(u16::MAX),
+            });
+            PnmEncoder::new(out).with_header(header).write_image(
+                &gray16(values),
+                width,
+                height,
+                ExtendedColorType::L16,
+            )
+        }
+        Format::Exr => {
+            let rgb: Vec<u8> = values
+                .iter()
+                .flat_map(|value| [*value; 3])
+                .flat_map(f32::to_ne_bytes)
+                .collect();
+            OpenExrEncoder::new(out).write_image(&rgb, width, height, ExtendedColorType::Rgb32F)
+        }
+        Format::Raw => {
+            for value in values {
+                out.write_all(&value.to_le_bytes())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Quantize normalized values to 16 bits, as native-endian bytes.
+fn gray16(values: &[f32]) -> Vec<u8> {
+    values
+        .iter()
+        .map(|value| (value.clamp(0.0, 1.0) * f32::from(u16::MAX)).round() as u16)
+        .flat_map(u16::to_ne_bytes)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ImageFormat;
+    use std::io::Cursor;
+
+    const VALUES: [f32; 6] = [0.0, 0.25, 0.5, 0.75, 1.0, 2.0];
+
+    fn encoded(format: Format) -> Vec<u8> {
+        let mut out = Cursor::new(Vec::new());
+        encode(format, 3, 2, &VALUES, &mut out).unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn gray_formats_round_trip_normalized() {
+        for (format, image_format) in [
+            (Format::Png, ImageFormat::Png),
+            (Format::Pgm, ImageFormat::Pnm),
+        ] {
+            let image = image::load_from_memory_with_format(&encoded(format), image_format)
+                .unwrap()
+                .into_luma16();
+            assert_eq!(image.dimensions(), (3, 2));
+            let pixels: Vec<u16> = image.pixels().map(|pixel| pixel.0[0]).collect();
+            assert_eq!(pixels, [0, 16384, 32768, 49151, 65535, 65535]);
+        }
+    }
+
+    #[test]
+    fn float_formats_keep_values() {
+        let exr = image::load_from_memory_with_format(&encoded(Format::Exr), ImageFormat::OpenExr)
+            .unwrap()
+            .into_rgb32f();
+        assert_eq!(exr.dimensions(), (3, 2));
+        let values: Vec<f32> = exr.pixels().map(|pixel| pixel.0[0]).collect();
+        assert_eq!(values, VALUES);
+
+        let raw = encoded(Format::Raw);
+        assert_eq!(raw.len(), VALUES.len() * 4);
+        assert_eq!(raw[20..24], 2.0_f32.to_le_bytes());
+    }
+}
@@ -0,0 +1,503 @@
+//! Command-line tool to render, inspect and compare noise.
+//!
+//! Reproduces the noise of a seed and key outside the game: render a
+//! region to an image, print its statistics, or diff two configurations.
+
+mod encode;
+
+use encode::Format;
+use msg_noise::math::{DVec2, UVec2};
+use msg_noise::{CustomNoise, NoiseBackend, NoiseRecipe, NoiseSource, SampleRegion};
+use noise::{Billow, MultiFractal, NoiseFn, Perlin, RidgedMulti, Simplex, Value, Worley};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: msg_noise <COMMAND> [OPTIONS]
+
+Commands:
+  render    Render a region to an image (requires --output)
+  stats     Print statistics of a region
+  diff      Compare two configurations; options prefixed with --b-
+            override the second one, e.g. --b-seed 2. Exits with 1 if
+            any sample differs
+
+Noise options:
+  --seed <N>              Base seed of the NoiseSource [default: 0]
+  --key <N>               Key passed to NoiseSource::create [default: 0]
+  --algorithm <NAME>      perlin, deterministic, billow, ridged, worley,
+                          simplex or value [default: perlin]
+  --recipe <FILE>         NoiseRecipe RON file; other options override it
+  --scale <F>             Input scale
+  --octaves <N>           Fractal octaves
+  --persistence <F>       Fractal persistence
+  --lacunarity <F>        Fractal lacunarity
+  --range <MIN,MAX>       Output range
+  --output-version <N>    NOISE_OUTPUT_VERSION to reproduce
+
+Region options:
+  --origin <X,Y>          Corner of the region [default: 0,0]
+  --size <W,H>            Size in input units [default: the resolution]
+  --resolution <W,H>      Samples per axis [default: 256,256]
+
+Output options:
+  -o, --output <FILE>     Output file; diff writes the absolute difference
+  --format <FORMAT>       png, pgm, exr or raw [default: from extension]
+
+Numbers accept 0x prefixes and underscores, e.g. --key 0x5445_5252.
+PNG and PGM store values normalized to the range; EXR and raw store
+them unchanged as 32-bit floats.";
+
+/// Options taking a value.
+const OPTIONS: [&str; 15] = [
+    "seed",
+    "key",
+    "algorithm",
+    "recipe",
+    "scale",
+    "octaves",
+    "persistence",
+    "lacunarity",
+    "range",
+    "output-version",
+    "origin",
+    "size",
+    "resolution",
+    "output",
+    "format",
+];
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match run(&args[0], &args[1..]) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {error}\nRun `msg_noise --help` for usage.");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(command: &str, args: &[String]) -> Result<ExitCode, String> {
+    let options = parse_options(args)?;
+    let (a, b): (BTreeMap<_, _>, BTreeMap<_, _>) = options
+        .into_iter()
+        .partition(|(name, _)| !name.starts_with("b-"));
+    if command != "diff" && !b.is_empty() {
+        return Err("--b- options are only valid for diff".into());
+    }
+    let config = Config::from_options(&a)?;
+
+    match command {
+        "render" => {
+            let path = a.get("output").ok_or("render requires --output")?;
+            let format = output_format(path, a.get("format"))?;
+            let values = config.render();
+            write_image(path, format, config.resolution, &values, |v| {
+                config.recipe.normalize(v)
+            })?;
+            println!(
+                "wrote {path} ({}x{}, {format:?})",
+                config.resolution.x, config.resolution.y
+            );
+        }
+        "stats" => print_stats(&config, &config.render()),
+        "diff" => {
+            let mut overridden = a.clone();
+            overridden.extend(
+                b.into_iter()
+                    .map(|(name, value)| (name["b-".len()..].to_string(), value)),
+            );
+            let other = Config::from_options(&overridden)?;
+            if other.resolution != config.resolution {
+                return Err("both configurations need the same resolution".into());
+            }
+
+            let differences: Vec<f64> = config
+                .render()
+                .iter()
+                .zip(other.render())
+                .map(|(a, b)| (a - b).abs())
+                .collect();
+            let max = differences.iter().copied().fold(0.0, f64::max);
+            let mean = differences.iter().sum::<f64>() / differences.len() as f64;
+            let differing = differences.iter().filter(|&&d| d != 0.0).count();
+            println!("samples:   {}", differences.len());
+            println!("differing: {differing}");
+            println!("max:       {max}");
+            println!("mean:      {mean}");
+
+            if let Some(path) = a.get("output") {
+                let format = output_format(path, a.get("format"))?;
+                let peak = if max > 0.0 { max } else { 1.0 };
+                write_image(path, format, config.resolution, &differences, |d| d / peak)?;
+                println!("wrote {path}");
+            }
+            if differing > 0 {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        _ => return Err(format!("unknown command `{command}`")),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Collect `--name value` pairs.
+fn parse_options(args: &[String]) -> Result<BTreeMap<String, String>, String> {
+    let mut options = BTreeMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let name = match arg.as_str() {
+            "-o" => "output",
+            other => other
+                .strip_prefix("--")
+                .ok_or_else(|| format!("unexpected argument `{arg}`"))?,
+        };
+        if !OPTIONS.contains(&name.strip_prefix("b-").unwrap_or(name)) {
+            return Err(format!("unknown option `{arg}`"));
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for `{arg}`"))?;
+        options.insert(name.to_string(), value.clone());
+    }
+    Ok(options)
+}
+
+/// Noise algorithm selected with `--algorithm`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Algorithm {
+    Perlin,
+    Deterministic,
+    Billow,
+    Ridged,
+    Worley,
+    Simplex,
+    Value,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "perlin" => Self::Perlin,
+            "deterministic" => Self::Deterministic,
+            "billow" => Self::Billow,
+            "ridged" => Self::Ridged,
+            "worley" => Self::Worley,
+            "simplex" => Self::Simplex,
+            "value" => Self::Value,
+            _ => return Err(format!("unknown algorithm `{name}`")),
+        })
+    }
+}
+
+/// One noise configuration and the region to sample.
+struct Config {
+    source: NoiseSource,
+    recipe: NoiseRecipe,
+    algorithm: Algorithm,
+    region: SampleRegion,
+    resolution: UVec2,
+}
+
+impl Config {
+    fn from_options(options: &BTreeMap<String, String>) -> Result<Self, String> {
+        let get = |name: &str| options.get(name).map(String::as_str);
+
+        let mut recipe = match get("recipe") {
+            Some(path) => {
+                let ron = std::fs::read_to_string(path)
+                    .map_err(|error| format!("cannot read {path}: {error}"))?;
+                NoiseRecipe::from_ron(&ron).map_err(|error| format!("{path}: {error}"))?
+            }
+            None => NoiseRecipe::default(),
+        };
+        if let Some(key) = get("key") {
+            recipe = recipe.with_key(parse_int(key)?);
+        }
+        if let Some(scale) = get("scale") {
+            recipe = recipe.with_scale(parse_float(scale)?);
+        }
+        let (mut octaves, mut persistence, mut lacunarity) = recipe.fractal();
+        if let Some(value) = get("octaves") {
+            octaves = parse_int(value)?;
+        }
+        if let Some(value) = get("persistence") {
+            persistence = parse_float(value)?;
+        }
+        if let Some(value) = get("lacunarity") {
+            lacunarity = parse_float(value)?;
+        }
+        recipe = recipe.with_fractal(octaves, persistence, lacunarity);
+        if let Some(range) = get("range") {
+            let (min, max) = parse_pair(range, parse_float)?;
+            recipe = recipe.with_range(min, max);
+        }
+
+        let mut source = NoiseSource::new(get("seed").map_or(Ok(0), parse_int)?);
+        if let Some(version) = get("output-version") {
+            source = source
+                .try_with_output_version(parse_int(version)?)
+                .map_err(|error| error.to_string())?;
+        }
+
+        let resolution = match get("resolution") {
+            Some(value) => {
+                let (x, y) = parse_pair(value, parse_int)?;
+                UVec2::new(x, y).max(UVec2::ONE)
+            }
+            None => UVec2::splat(256),
+        };
+        let origin = get("origin").map_or(Ok((0.0, 0.0)), |v| parse_pair(v, parse_float))?;
+        let size = match get("size") {
+            Some(value) => parse_pair(value, parse_float)?,
+            None => (f64::from(resolution.x), f64::from(resolution.y)),
+        };
+
+        Ok(Self {
+            source,
+            recipe,
+            algorithm: Algorithm::parse(get("algorithm").unwrap_or("perlin"))?,
+            region: SampleRegion::new(DVec2::from(origin), DVec2::from(size)),
+            resolution,
+        })
+    }
+
+    /// Sample the region row by row, in the recipe's range.
+    fn render(&self) -> Vec<f64> {
+        let sample = self.sampler();
+        let mut values = Vec::with_capacity((self.resolution.x * self.resolution.y) as usize);
+        for y in 0..self.resolution.y {
+            for x in 0..self.resolution.x {
+                let point = self.region.cell_center(UVec2::new(x, y), self.resolution);
+                values.push(sample(point.x, point.y));
+            }
+        }
+        values
+    }
+
+    fn sampler(&self) -> Box<dyn Fn(f64, f64) -> f64> {
+        let noise = self.recipe.build(&self.source);
+        let recipe = self.recipe.clone();
+        let (octaves, persistence, lacunarity) = recipe.fractal();
+        match self.algorithm {
+            Algorithm::Perlin => Box::new(move |x, y| recipe.sample(&noise, x, y)),
+            Algorithm::Deterministic => {
+                let noise = noise.with_backend(NoiseBackend::Deterministic);
+                Box::new(move |x, y| recipe.sample(&noise, x, y))
+            }
+            Algorithm::Billow => scaled(
+                noise.with_generator(
+                    Billow::<Perlin>::default()
+                        .set_octaves(octaves as usize)
+                        .set_persistence(persistence)
+                        .set_lacunarity(lacunarity),
+                ),
+            ),
+            Algorithm::Ridged => scaled(
+                noise.with_generator(
+                    RidgedMulti::<Perlin>::default()
+                        .set_octaves(octaves as usize)
+                        .set_persistence(persistence)
+                        .set_lacunarity(lacunarity),
+                ),
+            ),
+            Algorithm::Worley => scaled(noise.with_generator(Worley::default())),
+            Algorithm::Simplex => scaled(noise.with_generator(Simplex::default())),
+            Algorithm::Value => scaled(noise.with_generator(Value::default())),
+        }
+    }
+}
+
+fn scaled<G>(noise: CustomNoise<G>) -> Box<dyn Fn(f64, f64) -> f64>
+where
+    G: NoiseFn<f64, 2> + NoiseFn<f64, 3> + 'static,
+{
+    Box::new(move |x, y| noise.get_scaled(x, y))
+}
+
+fn print_stats(config: &Config, values: &[f64]) {
+    const BINS: usize = 10;
+    const WIDTH: f64 = 40.0;
+
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    println!("samples:  {}", values.len());
+    println!("min:      {min}");
+    println!("max:      {max}");
+    println!("mean:     {mean}");
+    println!("std dev:  {}", variance.sqrt());
+
+    let (low, high) = config.recipe.range();
+    let mut histogram = [0_usize; BINS];
+    for value in values {
+        let t = config.recipe.normalize(*value).clamp(0.0, 1.0);
+        histogram[((t * BINS as f64) as usize).min(BINS - 1)] += 1;
+    }
+    let peak = histogram.iter().copied().max().unwrap_or(1).max(1) as f64;
+    println!("histogram:");
+    for (bin, &samples) in histogram.iter().enumerate() {
+        let start = low + (high - low) * bin as f64 / BINS as f64;
+        let bar = "#".repeat((samples as f64 / peak * WIDTH).round() as usize);
+        println!("  {start:>10.4} | {bar} {samples}");
+    }
+}
+
+fn output_format(path: &str, format: Option<&String>) -> Result<Format, String> {
+    let name = match format {
+        Some(format) => format.as_str(),
+        None => Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .ok_or_else(|| format!("cannot tell the format of {path}, use --format"))?,
+    };
+    Format::parse(name).ok_or_else(|| format!("unknown format `{name}`"))
+}
+
+/// Write `values`, normalizing them with `normalize` for 16-bit formats.
+fn write_image(
+    path: &str,
+    format: Format,
+    resolution: UVec2,
+    values: &[f64],
+    normalize: impl Fn(f64) -> f64,
+) -> Result<(), String> {
+    let pixels: Vec<f32> = values
+        .iter()
+        .map(|&v| {
+            if format.is_normalized() {
+                normalize(v) as f32
+            } else {
+                v as f32
+            }
+        })
+        .collect();
+    let file = File::create(path).map_err(|error| format!("cannot create {path}: {error}"))?;
+    let mut out = BufWriter::new(file);
+    encode::encode(format, resolution.x, resolution.y, &pixels, &mut out)
+        .map_err(|error| format!("cannot write {path}: {error}"))?;
+    out.flush()
+        .map_err(|error| format!("cannot write {path}: {error}"))
+}
+
+fn parse_int<T: TryFrom<u64>>(value: &str) -> Result<T, String> {
+    let digits = value.replace('_', "");
+    let parsed = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => digits.parse(),
+    };
+    parsed
+        .ok()
+        .and_then(|n| T::try_from(n).ok())
+        .ok_or_else(|| format!("invalid number `{value}`"))
+}
+
+fn parse_float(value: &str) -> Result<f64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number `{value}`"))
+}
+
+/// Parse `A,B`, or a single value used for both.
+fn parse_pair<T: Copy>(
+    value: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<(T, T), String> {
+    match value.split_once(',') {
+        Some((a, b)) => Ok((parse(a.trim())?, parse(b.trim())?)),
+        None => {
+            let v = parse(value)?;
+            Ok((v, v))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &[&str]) -> BTreeMap<String, String> {
+        let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+        parse_options(&args).unwrap()
+    }
+
+    #[test]
+    fn options_override_recipe_defaults() {
+        let config = Config::from_options(&options(&[
+            "--seed",
+            "0x10",
+            "--key",
+            "0x5445_5252",
+            "--octaves",
+            "3",
+            "--range",
+            "-1,1",
+            "--resolution",
+            "8",
+        ]))
+        .unwrap();
+        assert_eq!(config.source.seed(), 16);
+        assert_eq!(config.recipe.key(), 0x5445_5252);
+        assert_eq!(config.recipe.fractal(), (3, 0.5, 2.0));
+        assert_eq!(config.recipe.range(), (-1.0, 1.0));
+        assert_eq!(config.resolution, UVec2::splat(8));
+        assert_eq!(config.region.size, DVec2::splat(8.0));
+    }
+
+    #[test]
+    fn render_matches_library_sampling() {
+        let config = Config::from_options(&options(&[
+            "--seed",
+            "7",
+            "--key",
+            "3",
+            "--resolution",
+            "4,2",
+        ]))
+        .unwrap();
+        let noise = NoiseSource::new(7).create(3);
+        let values = config.render();
+        assert_eq!(values.len(), 8);
+        assert_eq!(values[5], config.recipe.sample(&noise, 1.5, 1.5));
+    }
+
+    #[test]
+    fn empty_range_normalizes_to_finite_values() {
+        let config =
+            Config::from_options(&options(&["--range", "1,1", "--resolution", "4"])).unwrap();
+        assert!(
+            config
+                .render()
+                .iter()
+                .all(|&v| config.recipe.normalize(v).is_finite())
+        );
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        let parse = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+            parse_options(&args)
+        };
+        assert!(parse(&["--colour", "red"]).is_err());
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["seed", "1"]).is_err());
+        assert!(parse(&["--b-seed", "1"]).is_ok());
+        assert!(Algorithm::parse("cellular").is_err());
+        assert!(parse_int::<u32>("0x1_0000_0000").is_err());
+        assert!(Config::from_options(&options(&["--output-version", "99"])).is_err());
+    }
+}
@@ -0,0 +1,382 @@
+//! Biome classification from multiple noise layers.
+//!
+//! A [`BiomeMap`] samples named climate layers (temperature, moisture,
+//! elevation, ...) and picks the [`Biome`] whose constraints best match the
+//! sampled values. Constraints are either Whittaker-style ranges or points
+//! in parameter space; a biome's distance is zero inside all of its ranges
+//! and grows with the distance to its points, and the closest biome wins.
+
+use crate::{Noise, SampleMode, SampleRegion};
+use glam::UVec2;
+
+/// A constraint on one layer's value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Constraint {
+    /// Value within `min..=max`.
+    Range(f64, f64),
+    /// Value as close as possible to a center.
+    Point(f64),
+}
+
+impl Constraint {
+    fn distance(self, value: f64) -> f64 {
+        match self {
+            Self::Range(min, max) => (min - value).max(value - max).max(0.0),
+            Self::Point(center) => (value - center).abs(),
+        }
+    }
+}
+
+/// A biome entry in a [`BiomeMap`] table.
+///
+/// Layers the biome does not constrain are ignored when classifying.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Biome {
+    id: u32,
+    constraints: Vec<(String, Constraint)>,
+}
+
+impl Biome {
+    /// Create a biome with the given id and no constraints.
+    #[must_use]
+    pub fn new(id: u32) -> Self {
+        Self {
+            id,
+            constraints: Vec::new(),
+        }
+    }
+
+    /// Require `layer` to lie within `min..=max`.
+    #[must_use]
+    pub fn with_range(mut self, layer: impl Into<String>, min: f64, max: f64) -> Self {
+        self.constraints
+            .push((layer.into(), Constraint::Range(min.min(max), min.max(max))));
+        self
+    }
+
+    /// Prefer `layer` values close to `value`.
+    #[must_use]
+    pub fn with_point(mut self, layer: impl Into<String>, value: f64) -> Self {
+        self.constraints
+            .push((layer.into(), Constraint::Point(value)));
+        self
+    }
+
+    /// Get the biome id.
+    #[must_use]
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+/// The biome at a point together with blend weights.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BiomeSample {
+    /// Id of the best matching biome.
+    pub id: u32,
+    /// Biome ids and weights summing to 1.0, heaviest first. Contains
+    /// only `id` away from biome borders.
+    pub weights: Vec<(u32, f64)>,
+}
+
+impl BiomeSample {
+    /// Weight of biome `id`, or 0.0 if it does not contribute.
+    #[must_use]
+    pub fn weight(&self, id: u32) -> f64 {
+        self.weights
+            .iter()
+            .find(|(biome, _)| *biome == id)
+            .map_or(0.0, |(_, weight)| *weight)
+    }
+}
+
+/// Biome ids of a rasterized region.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BiomeGrid {
+    resolution: UVec2,
+    ids: Vec<Option<u32>>,
+}
+
+impl BiomeGrid {
+    /// Number of cells per axis.
+    #[must_use]
+    pub fn resolution(&self) -> UVec2 {
+        self.resolution
+    }
+
+    /// Biome ids row by row, `None` where the map has no biomes.
+    #[must_use]
+    pub fn ids(&self) -> &[Option<u32>] {
+        &self.ids
+    }
+
+    /// Biome id of `cell`.
+    #[must_use]
+    pub fn get(&self, cell: UVec2) -> Option<u32> {
+        if cell.cmpge(self.resolution).any() {
+            return None;
+        }
+        self.ids[(cell.y * self.resolution.x + cell.x) as usize]
+    }
+}
+
+/// Classifies points into biomes from named noise layers.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{Biome, BiomeMap, NoiseSource, SampleMode};
+///
+/// const DESERT: u32 = 0;
+/// const FOREST: u32 = 1;
+/// const TUNDRA: u32 = 2;
+///
+/// let source = NoiseSource::new(12345);
+/// let biomes = BiomeMap::new()
+///     .with_layer("temperature", &source.create(0x5445_4D50).with_scale(0.002), SampleMode::Normalized)
+///     .with_layer("moisture", &source.create(0x4D4F_4953).with_scale(0.003), SampleMode::Normalized)
+///     .with_biome(Biome::new(DESERT).with_range("temperature", 0.6, 1.0).with_range("moisture", 0.0, 0.4))
+///     .with_biome(Biome::new(FOREST).with_range("temperature", 0.3, 1.0).with_range("moisture", 0.4, 1.0))
+///     .with_biome(Biome::new(TUNDRA).with_range("temperature", 0.0, 0.3))
+///     .with_blend_width(0.05);
+///
+/// let sample = biomes.sample(100.0, 200.0).unwrap();
+/// println!("biome {} ({:.0}% forest)", sample.id, sample.weight(FOREST) * 100.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BiomeMap {
+    layers: Vec<(String, Noise, SampleMode)>,
+    biomes: Vec<Biome>,
+    /// Constraints of each biome indexed by layer.
+    resolved: Vec<Vec<Option<Constraint>>>,
+    blend_width: f64,
+}
+
+impl Default for BiomeMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BiomeMap {
+    /// Create an empty biome map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            biomes: Vec::new(),
+            resolved: Vec::new(),
+            blend_width: 0.05,
+        }
+    }
+
+    /// Add a named layer sampled with `mode`. Adding a layer with an
+    /// existing name replaces it.
+    #[must_use]
+    pub fn with_layer(mut self, name: impl Into<String>, noise: &Noise, mode: SampleMode) -> Self {
+        let name = name.into();
+        match self.layers.iter_mut().find(|(layer, ..)| *layer == name) {
+            Some(layer) => *layer = (name, noise.clone(), mode),
+            None => self.layers.push((name, noise.clone(), mode)),
+        }
+        self.resolve();
+        self
+    }
+
+    /// Add a biome to the table. When several biomes match equally well
+    /// the one added first wins.
+    #[must_use]
+    pub fn with_biome(mut self, biome: Biome) -> Self {
+        self.biomes.push(biome);
+        self.resolve();
+        self
+    }
+
+    /// Set the parameter-space distance over which neighboring biomes
+    /// blend. Zero disables blending. Defaults to 0.05.
+    #[must_use]
+    pub fn with_blend_width(mut self, blend_width: f64) -> Self {
+        self.blend_width = blend_width.max(0.0);
+        self
+    }
+
+    /// Get the biome table.
+    #[must_use]
+    pub fn biomes(&self) -> &[Biome] {
+        &self.biomes
+    }
+
+    /// Layer values at a point, in the order the layers were added.
+    #[must_use]
+    pub fn parameters(&self, x: f64, y: f64) -> Vec<f64> {
+        self.layers
+            .iter()
+            .map(|(_, noise, mode)| noise.sample(*mode, x, y))
+            .collect()
+    }
+
+    /// Id of the best matching biome at a point, or `None` if the table is
+    /// empty.
+    #[must_use]
+    pub fn biome_at(&self, x: f64, y: f64) -> Option<u32> {
+        let parameters = self.parameters(x, y);
+        self.distances(&parameters)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// Best matching biome and blend weights at a point, or `None` if the
+    /// table is empty.
+    ///
+    /// Each biome within the blend width of the best match gets a weight
+    /// that falls off smoothly with its extra distance, so weights change
+    /// continuously across biome borders.
+    #[must_use]
+    pub fn sample(&self, x: f64, y: f64) -> Option<BiomeSample> {
+        let parameters = self.parameters(x, y);
+        let distances: Vec<(u32, f64)> = self.distances(&parameters).collect();
+        let &(id, nearest) = distances.iter().min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        let mut weights: Vec<(u32, f64)> = if self.blend_width > 0.0 {
+            distances
+                .iter()
+                .filter_map(|&(biome, distance)| {
+                    let t = 1.0 - (distance - nearest) / self.blend_width;
+                    (t > 0.0).then_some((biome, t * t * (3.0 - 2.0 * t)))
+                })
+                .collect()
+        } else {
+            vec![(id, 1.0)]
+        };
+
+        // Table entries sharing an id count once, with their best weight
+        weights.sort_by_key(|(biome, _)| *biome);
+        weights.dedup_by(|a, b| {
+            let same = a.0 == b.0;
+            if same {
+                b.1 = b.1.max(a.1);
+            }
+            same
+        });
+        let total: f64 = weights.iter().map(|(_, w)| w).sum();
+        for (_, weight) in &mut weights {
+            *weight /= total;
+        }
+        // Heaviest first, with the best match first among equal weights
+        weights.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| (b.0 == id).cmp(&(a.0 == id)))
+        });
+
+        Some(BiomeSample { id, weights })
+    }
+
+    /// Classify the center of each cell of `region` divided into
+    /// `resolution` cells.
+    #[must_use]
+    pub fn rasterize(&self, region: SampleRegion, resolution: UVec2) -> BiomeGrid {
+        let resolution = resolution.max(UVec2::ONE);
+        let mut ids = Vec::with_capacity((resolution.x * resolution.y) as usize);
+        for y in 0..resolution.y {
+            for x in 0..resolution.x {
+                let p = region.cell_center(UVec2::new(x, y), resolution);
+                ids.push(self.biome_at(p.x, p.y));
+            }
+        }
+        BiomeGrid { resolution, ids }
+    }
+
+    /// Distance of each biome from the parameters, in table order.
+    fn distances<'a>(&'a self, parameters: &'a [f64]) -> impl Iterator<Item = (u32, f64)> + 'a {
+        self.biomes
+            .iter()
+            .zip(&self.resolved)
+            .map(move |(biome, constraints)| {
+                let squared: f64 = constraints
+                    .iter()
+                    .zip(parameters)
+                    .filter_map(|(constraint, value)| {
+                        constraint.map(|c| c.distance(*value).powi(2))
+                    })
+                    .sum();
+                (biome.id, squared.sqrt())
+            })
+    }
+
+    /// Match biome constraints to layer indices.
+    fn resolve(&mut self) {
+        self.resolved = self
+            .biomes
+            .iter()
+            .map(|biome| {
+                self.layers
+                    .iter()
+                    .map(|(name, ..)| {
+                        biome
+                            .constraints
+                            .iter()
+                            .find(|(layer, _)| layer == name)
+                            .map(|(_, constraint)| *constraint)
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_pick_matching_biome() {
+        let layer = Noise::new(7).with_scale(0.01);
+        let map = BiomeMap::new()
+            .with_layer("heat", &layer, SampleMode::Normalized)
+            .with_biome(Biome::new(1).with_range("heat", 0.0, 0.5))
+            .with_biome(Biome::new(2).with_range("heat", 0.5, 1.0))
+            .with_blend_width(0.0);
+
+        for i in 0..50 {
+            let x = f64::from(i) * 7.0;
+            let heat = layer.get_normalized(x, 0.0);
+            let expected = if heat <= 0.5 { 1 } else { 2 };
+            assert_eq!(map.biome_at(x, 0.0), Some(expected));
+        }
+    }
+
+    #[test]
+    fn default_matches_new() {
+        assert_eq!(BiomeMap::default().blend_width, BiomeMap::new().blend_width);
+    }
+
+    #[test]
+    fn nearest_point_blends_near_border() {
+        let layer = Noise::new(7).with_scale(0.01);
+        let map = BiomeMap::new()
+            .with_layer("heat", &layer, SampleMode::Normalized)
+            .with_biome(Biome::new(1).with_point("heat", 0.3))
+            .with_biome(Biome::new(2).with_point("heat", 0.7))
+            .with_blend_width(0.2);
+
+        for i in 0..200 {
+            let x = f64::from(i) * 3.0;
+            let heat = layer.get_normalized(x, 0.0);
+            let sample = map.sample(x, 0.0).unwrap();
+            let total: f64 = sample.weights.iter().map(|(_, w)| w).sum();
+            assert!((total - 1.0).abs() < 1e-9);
+            assert_eq!(sample.id, sample.weights[0].0);
+            if (heat - 0.5).abs() > 0.1 {
+                assert_eq!(sample.weights.len(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_table_has_no_biome() {
+        let map = BiomeMap::new().with_layer("heat", &Noise::new(1), SampleMode::Normalized);
+
+        assert_eq!(map.biome_at(0.0, 0.0), None);
+        assert!(map.sample(0.0, 0.0).is_none());
+    }
+}
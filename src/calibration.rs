@@ -0,0 +1,442 @@
+//! Empirical range calibration for [`Noise`].
+//!
+//! Raw Perlin noise is concentrated around zero and fractal noise shrinks
+//! further with every octave, so thresholds on
+//! [`Noise::get_normalized`] behave differently per layer. A
+//! [`NoiseCalibration`] measures the actual distribution of a configured
+//! [`Noise`] on a deterministic sample grid and maps values onto a true
+//! 0.0 to 1.0 range.
+
+use crate::{Noise, float};
+use alloc::vec;
+use alloc::vec::Vec;
+use glam::DVec2;
+
+/// Number of noise-space units covered by the default calibration region.
+const DEFAULT_NOISE_SPACE_EXTENT: f64 = 64.0;
+
+/// Fractional part of the golden ratio, used to jitter sample positions
+/// off the Perlin lattice where raw noise is always zero.
+const GOLDEN_FRACTION: f64 = 0.618_033_988_749_895;
+
+/// Which sampler a calibration measures.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CalibrationSampler {
+    /// [`Noise::get_raw`].
+    Raw,
+    /// [`Noise::get_raw_3d`], over a cube starting at the region origin
+    /// and the [z origin](CalibrationSettings::with_z_origin).
+    Raw3d,
+    /// [`Noise::get_fractal`] with the given parameters.
+    Fractal {
+        /// Number of octaves.
+        octaves: u32,
+        /// Amplitude multiplier per octave.
+        persistence: f64,
+        /// Frequency multiplier per octave.
+        lacunarity: f64,
+    },
+}
+
+/// Settings for measuring a [`NoiseCalibration`].
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{CalibrationSampler, CalibrationSettings, Noise};
+///
+/// let settings = CalibrationSettings::default()
+///     .with_sampler(CalibrationSampler::Fractal {
+///         octaves: 4,
+///         persistence: 0.5,
+///         lacunarity: 2.0,
+///     })
+///     .with_percentiles(0.01, 0.99);
+///
+/// let terrain = Noise::new(12345).calibrated(&settings);
+/// let height = terrain.get_calibrated(10.0, 20.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CalibrationSettings {
+    sampler: CalibrationSampler,
+    resolution: u32,
+    origin: DVec2,
+    z_origin: f64,
+    extent: Option<f64>,
+    low_percentile: f64,
+    high_percentile: f64,
+    bins: usize,
+}
+
+impl Default for CalibrationSettings {
+    fn default() -> Self {
+        Self {
+            sampler: CalibrationSampler::Raw,
+            resolution: 128,
+            origin: DVec2::ZERO,
+            z_origin: 0.0,
+            extent: None,
+            low_percentile: 0.0,
+            high_percentile: 1.0,
+            bins: 256,
+        }
+    }
+}
+
+impl CalibrationSettings {
+    /// Set which sampler to measure.
+    #[must_use]
+    pub fn with_sampler(mut self, sampler: CalibrationSampler) -> Self {
+        self.sampler = sampler;
+        self
+    }
+
+    /// Set the number of samples per axis for 2D samplers.
+    ///
+    /// 3D samplers use the same total number of samples.
+    #[must_use]
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution.max(2);
+        self
+    }
+
+    /// Set the square region (in input coordinates) to sample.
+    ///
+    /// By default the region starts at the origin and covers 64 units in
+    /// noise space, derived from the noise scale.
+    #[must_use]
+    pub fn with_region(mut self, origin: DVec2, extent: f64) -> Self {
+        self.origin = origin;
+        self.extent = Some(extent);
+        self
+    }
+
+    /// Set the z coordinate where the cube sampled by
+    /// [`CalibrationSampler::Raw3d`] starts. Defaults to 0.0.
+    #[must_use]
+    pub fn with_z_origin(mut self, z: f64) -> Self {
+        self.z_origin = z;
+        self
+    }
+
+    /// Set the percentiles mapped to 0.0 and 1.0 by
+    /// [`NoiseCalibration::normalize`].
+    ///
+    /// The defaults of 0.0 and 1.0 use the measured minimum and maximum.
+    /// Both are clamped to 0.0 to 1.0 and swapped if `low > high`.
+    #[must_use]
+    pub fn with_percentiles(mut self, low: f64, high: f64) -> Self {
+        let (low, high) = if low > high { (high, low) } else { (low, high) };
+        self.low_percentile = low.clamp(0.0, 1.0);
+        self.high_percentile = high.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the number of histogram bins used for equalization.
+    #[must_use]
+    pub fn with_bins(mut self, bins: usize) -> Self {
+        self.bins = bins.max(1);
+        self
+    }
+}
+
+/// Measured value distribution of a [`Noise`] configuration.
+///
+/// Calibrations operate on values in the sampler's raw domain, so a
+/// calibration measured with [`CalibrationSampler::Fractal`] should only
+/// be applied to fractal values with the same parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoiseCalibration {
+    min: f64,
+    max: f64,
+    low: f64,
+    high: f64,
+    cdf: Vec<f64>,
+}
+
+impl NoiseCalibration {
+    /// Measure the distribution of `noise` using `settings`.
+    ///
+    /// Sampling is deterministic: the same noise and settings always
+    /// produce the same calibration.
+    #[must_use]
+    pub fn measure(noise: &Noise, settings: &CalibrationSettings) -> Self {
+        let mut samples = collect_samples(noise, settings);
+        Self::from_samples(&mut samples, settings)
+    }
+
+    /// Build a calibration from raw sample values.
+    ///
+    /// Non-finite values are ignored. The slice is sorted in place.
+    #[must_use]
+    pub fn from_samples(samples: &mut Vec<f64>, settings: &CalibrationSettings) -> Self {
+        samples.retain(|value| value.is_finite());
+        samples.sort_by(f64::total_cmp);
+
+        let (Some(&min), Some(&max)) = (samples.first(), samples.last()) else {
+            return Self::identity(settings.bins);
+        };
+
+        let low = percentile(samples, settings.low_percentile);
+        let high = percentile(samples, settings.high_percentile);
+
+        let bins = settings.bins;
+        let mut counts = vec![0_usize; bins];
+        let span = max - min;
+        for &value in samples.iter() {
+            counts[bin_index(value, min, span, bins)] += 1;
+        }
+
+        let total = samples.len() as f64;
+        let mut cdf = Vec::with_capacity(bins + 1);
+        let mut running = 0_usize;
+        cdf.push(0.0);
+        for count in counts {
+            running += count;
+            cdf.push(running as f64 / total);
+        }
+
+        Self {
+            min,
+            max,
+            low,
+            high,
+            cdf,
+        }
+    }
+
+    /// A calibration that assumes the theoretical -1.0 to 1.0 range with a
+    /// uniform distribution.
+    #[must_use]
+    pub fn identity(bins: usize) -> Self {
+        let bins = bins.max(1);
+        Self {
+            min: -1.0,
+            max: 1.0,
+            low: -1.0,
+            high: 1.0,
+            cdf: (0..=bins).map(|i| i as f64 / bins as f64).collect(),
+        }
+    }
+
+    /// Smallest measured value.
+    #[must_use]
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// Largest measured value.
+    #[must_use]
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Value at the low percentile, mapped to 0.0 by [`Self::normalize`].
+    #[must_use]
+    pub fn low(&self) -> f64 {
+        self.low
+    }
+
+    /// Value at the high percentile, mapped to 1.0 by [`Self::normalize`].
+    #[must_use]
+    pub fn high(&self) -> f64 {
+        self.high
+    }
+
+    /// Linearly map a raw value from the calibrated range to 0.0 to 1.0.
+    ///
+    /// Values outside the calibrated percentiles are clamped.
+    #[must_use]
+    pub fn normalize(&self, value: f64) -> f64 {
+        let span = self.high - self.low;
+        if span <= 0.0 {
+            return 0.5;
+        }
+        ((value - self.low) / span).clamp(0.0, 1.0)
+    }
+
+    /// Map a raw value through the measured cumulative distribution.
+    ///
+    /// The output is approximately uniformly distributed between 0.0 and
+    /// 1.0, so a threshold of 0.3 selects roughly 30% of the area.
+    #[must_use]
+    pub fn equalize(&self, value: f64) -> f64 {
+        let span = self.max - self.min;
+        if value <= self.min || span <= 0.0 {
+            return 0.0;
+        }
+        if value >= self.max {
+            return 1.0;
+        }
+
+        let bins = self.cdf.len() - 1;
+        let position = (value - self.min) / span * bins as f64;
+        let bin = (position as usize).min(bins - 1);
+        let fraction = position - bin as f64;
+        self.cdf[bin] + fraction * (self.cdf[bin + 1] - self.cdf[bin])
+    }
+}
+
+/// Sample `noise` on the deterministic calibration grid.
+fn collect_samples(noise: &Noise, settings: &CalibrationSettings) -> Vec<f64> {
+    let extent = settings.extent.unwrap_or_else(|| {
+        let frequency = noise.scale.abs().max_element();
+        if frequency > 0.0 {
+            DEFAULT_NOISE_SPACE_EXTENT / frequency
+        } else {
+            DEFAULT_NOISE_SPACE_EXTENT
+        }
+    });
+    let origin = settings.origin;
+
+    match settings.sampler {
+        CalibrationSampler::Raw3d => {
+            let total = f64::from(settings.resolution) * f64::from(settings.resolution);
+            let resolution = float::ceil(float::cbrt(total)) as u32;
+            let step = extent / f64::from(resolution);
+            let mut samples = Vec::with_capacity(resolution.pow(3) as usize);
+            for k in 0..resolution {
+                let z = settings.z_origin + jittered(k, step);
+                for j in 0..resolution {
+                    let y = origin.y + jittered(j, step);
+                    for i in 0..resolution {
+                        let x = origin.x + jittered(i, step);
+                        samples.push(noise.get_raw_3d(x, y, z));
+                    }
+                }
+            }
+            samples
+        }
+        sampler => {
+            let resolution = settings.resolution;
+            let step = extent / f64::from(resolution);
+            let mut samples = Vec::with_capacity(resolution.pow(2) as usize);
+            for j in 0..resolution {
+                let y = origin.y + jittered(j, step);
+                for i in 0..resolution {
+                    let x = origin.x + jittered(i, step);
+                    samples.push(match sampler {
+                        CalibrationSampler::Fractal {
+                            octaves,
+                            persistence,
+                            lacunarity,
+                        } => noise.get_fractal(x, y, octaves, persistence, lacunarity),
+                        _ => noise.get_raw(x, y),
+                    });
+                }
+            }
+            samples
+        }
+    }
+}
+
+/// Position of grid sample `index`, jittered off integer multiples of `step`.
+fn jittered(index: u32, step: f64) -> f64 {
+    let jitter = float::fract(f64::from(index) * GOLDEN_FRACTION);
+    (f64::from(index) + jitter) * step
+}
+
+/// Value at percentile `p` (0.0 to 1.0) of sorted samples.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let position = p * (sorted.len() - 1) as f64;
+    let lower = float::floor(position) as usize;
+    let upper = float::ceil(position) as usize;
+    let fraction = position - lower as f64;
+    sorted[lower] + fraction * (sorted[upper] - sorted[lower])
+}
+
+/// Histogram bin containing `value`.
+fn bin_index(value: f64, min: f64, span: f64, bins: usize) -> usize {
+    if span <= 0.0 {
+        return 0;
+    }
+    (((value - min) / span * bins as f64) as usize).min(bins - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration_is_deterministic() {
+        let noise = Noise::new(42);
+        let settings = CalibrationSettings::default().with_resolution(32);
+
+        let first = NoiseCalibration::measure(&noise, &settings);
+        let second = NoiseCalibration::measure(&noise, &settings);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn fractal_calibration_is_tighter_than_theoretical() {
+        let noise = Noise::new(42);
+        let settings = CalibrationSettings::default().with_sampler(CalibrationSampler::Fractal {
+            octaves: 6,
+            persistence: 0.5,
+            lacunarity: 2.0,
+        });
+        let calibration = NoiseCalibration::measure(&noise, &settings);
+
+        assert!(calibration.min() > -1.0 && calibration.min() < 0.0);
+        assert!(calibration.max() < 1.0 && calibration.max() > 0.0);
+        assert!((calibration.normalize(calibration.min())).abs() < f64::EPSILON);
+        assert!((calibration.normalize(calibration.max()) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn raw_3d_calibration_follows_origin() {
+        let noise = Noise::new(42);
+        let settings = CalibrationSettings::default()
+            .with_sampler(CalibrationSampler::Raw3d)
+            .with_resolution(16);
+        let far = DVec2::splat(5000.0);
+
+        let moved = collect_samples(&noise, &settings.clone().with_region(far, 100.0));
+        let cube = collect_samples(
+            &noise,
+            &settings.with_region(far, 100.0).with_z_origin(-2500.0),
+        );
+        let step = 100.0 / 7.0;
+        assert_eq!(
+            cube[0],
+            noise.get_raw_3d(
+                far.x + jittered(0, step),
+                far.y + jittered(0, step),
+                -2500.0 + jittered(0, step)
+            )
+        );
+        assert_ne!(moved, cube);
+    }
+
+    #[test]
+    fn reversed_percentiles_are_swapped() {
+        let mut samples: Vec<f64> = (0..=100).map(f64::from).collect();
+        let reversed = CalibrationSettings::default().with_percentiles(0.9, 0.1);
+        let calibration = NoiseCalibration::from_samples(&mut samples.clone(), &reversed);
+        let expected = NoiseCalibration::from_samples(
+            &mut samples,
+            &CalibrationSettings::default().with_percentiles(0.1, 0.9),
+        );
+        assert_eq!(calibration, expected);
+        assert!(calibration.normalize(20.0) < calibration.normalize(80.0));
+    }
+
+    #[test]
+    fn equalize_is_monotonic_and_bounded() {
+        let mut samples: Vec<f64> = (0..1000).map(|i| (f64::from(i) / 1000.0).powi(3)).collect();
+        let calibration =
+            NoiseCalibration::from_samples(&mut samples, &CalibrationSettings::default());
+
+        let mut previous = 0.0;
+        for i in 0..=100 {
+            let value = calibration.equalize(f64::from(i) / 100.0);
+            assert!((0.0..=1.0).contains(&value));
+            assert!(value >= previous);
+            previous = value;
+        }
+
+        // Cubed inputs cluster near zero, so equalization spreads them out
+        assert!(calibration.equalize(0.125) > 0.45);
+    }
+}
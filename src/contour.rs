@@ -0,0 +1,465 @@
+//! Contour extraction from 2D noise fields.
+//!
+//! [`ContourBuilder`] samples a field on a regular grid and traces the
+//! lines where it crosses one or more iso levels with marching squares,
+//! for coastlines, region outlines and fog-of-war borders. Contours are
+//! oriented so that values at or above the level lie on their left:
+//! closed loops around high ground wind counter-clockwise, holes wind
+//! clockwise.
+
+use crate::{Noise, SampleMode, SampleRegion};
+use glam::{DVec2, IVec2, UVec2};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+/// Corner offsets of a grid cell in counter-clockwise order.
+const CORNERS: [IVec2; 4] = [
+    IVec2::new(0, 0),
+    IVec2::new(1, 0),
+    IVec2::new(1, 1),
+    IVec2::new(0, 1),
+];
+
+/// A polyline or polygon traced along an iso level.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Contour {
+    /// Points in world coordinates. Closed contours do not repeat the
+    /// first point at the end.
+    pub points: Vec<DVec2>,
+    /// Whether the last point connects back to the first.
+    pub closed: bool,
+}
+
+impl Contour {
+    /// Total length of the contour, including the closing segment.
+    #[must_use]
+    pub fn length(&self) -> f64 {
+        let open: f64 = self.points.windows(2).map(|w| w[0].distance(w[1])).sum();
+        match (self.closed, self.points.first(), self.points.last()) {
+            (true, Some(first), Some(last)) => open + last.distance(*first),
+            _ => open,
+        }
+    }
+
+    /// Signed area enclosed by a closed contour.
+    ///
+    /// Positive for counter-clockwise loops around high ground, negative
+    /// for holes. Open contours have no area.
+    #[must_use]
+    pub fn signed_area(&self) -> f64 {
+        if !self.closed {
+            return 0.0;
+        }
+        let n = self.points.len();
+        (0..n)
+            .map(|i| self.points[i].perp_dot(self.points[(i + 1) % n]))
+            .sum::<f64>()
+            * 0.5
+    }
+
+    /// Simplify the contour with the Ramer-Douglas-Peucker algorithm,
+    /// removing points closer than `tolerance` to the simplified line.
+    ///
+    /// Endpoints of open contours are preserved, so simplified chunks
+    /// still [`stitch`](Self::stitch) together.
+    #[must_use]
+    pub fn simplify(&self, tolerance: f64) -> Self {
+        if self.points.len() < 3 || tolerance <= 0.0 {
+            return self.clone();
+        }
+
+        let mut points = self.points.clone();
+        if self.closed {
+            points.push(points[0]);
+        }
+        let mut keep = vec![false; points.len()];
+        keep[0] = true;
+        keep[points.len() - 1] = true;
+        douglas_peucker(&points, 0, points.len() - 1, tolerance, &mut keep);
+
+        let mut simplified: Vec<DVec2> = points
+            .into_iter()
+            .zip(keep)
+            .filter_map(|(point, keep)| keep.then_some(point))
+            .collect();
+        if self.closed {
+            simplified.pop();
+        }
+        Self {
+            points: simplified,
+            closed: self.closed,
+        }
+    }
+
+    /// Join open contours whose endpoints lie within `tolerance` of each
+    /// other, such as the pieces of a coastline extracted from adjacent
+    /// chunks.
+    ///
+    /// Contours that end where they started become closed. Closed input
+    /// contours are passed through unchanged.
+    #[must_use]
+    pub fn stitch(contours: impl IntoIterator<Item = Contour>, tolerance: f64) -> Vec<Contour> {
+        let (mut stitched, mut open): (Vec<Contour>, Vec<Contour>) =
+            contours.into_iter().partition(|c| c.closed);
+
+        let meets = |a: Option<&DVec2>, b: Option<&DVec2>| {
+            a.zip(b).is_some_and(|(a, b)| a.distance(*b) <= tolerance)
+        };
+        while let Some(mut current) = open.pop() {
+            loop {
+                if current.points.len() > 2 && meets(current.points.last(), current.points.first())
+                {
+                    current.points.pop();
+                    current.closed = true;
+                    break;
+                }
+                // Extend forwards from the end, then backwards from the start
+                if let Some(i) = open
+                    .iter()
+                    .position(|c| meets(current.points.last(), c.points.first()))
+                {
+                    let next = open.swap_remove(i);
+                    current.points.extend_from_slice(&next.points[1..]);
+                } else if let Some(i) = open
+                    .iter()
+                    .position(|c| meets(c.points.last(), current.points.first()))
+                {
+                    let mut previous = open.swap_remove(i);
+                    previous.points.extend_from_slice(&current.points[1..]);
+                    current = previous;
+                } else {
+                    break;
+                }
+            }
+            stitched.push(current);
+        }
+
+        stitched
+    }
+}
+
+/// Mark the points between `start` and `end` that deviate from the line
+/// between them by more than `tolerance`.
+fn douglas_peucker(points: &[DVec2], start: usize, end: usize, tolerance: f64, keep: &mut [bool]) {
+    if end <= start + 1 {
+        return;
+    }
+
+    let (a, b) = (points[start], points[end]);
+    let (farthest, distance) = (start + 1..end)
+        .map(|i| (i, distance_to_segment(points[i], a, b)))
+        .fold((start, 0.0), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        });
+
+    if distance > tolerance {
+        keep[farthest] = true;
+        douglas_peucker(points, start, farthest, tolerance, keep);
+        douglas_peucker(points, farthest, end, tolerance, keep);
+    }
+}
+
+fn distance_to_segment(point: DVec2, a: DVec2, b: DVec2) -> f64 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared <= f64::EPSILON {
+        return point.distance(a);
+    }
+    let t = ((point - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
+/// Contours traced at a single iso level.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContourLevel {
+    /// The iso level.
+    pub level: f64,
+    /// Contours at this level.
+    pub contours: Vec<Contour>,
+}
+
+/// Builder for extracting contours from a 2D field.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::math::DVec2;
+/// use msg_noise::{ContourBuilder, Noise, SampleMode, SampleRegion};
+///
+/// let terrain = Noise::new(12345).with_scale(0.02);
+/// let levels = ContourBuilder::from_noise(&terrain, SampleMode::Normalized)
+///     .with_region(SampleRegion::new(DVec2::ZERO, DVec2::splat(128.0)))
+///     .with_levels([0.5, 0.7])
+///     .with_simplify(0.25)
+///     .extract();
+///
+/// for contour in &levels[0].contours {
+///     if contour.closed {
+///         println!("island with area {:.1}", contour.signed_area());
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ContourBuilder {
+    sampler: Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>,
+    region: SampleRegion,
+    resolution: UVec2,
+    levels: Vec<f64>,
+    simplify: Option<f64>,
+}
+
+impl ContourBuilder {
+    /// Create a builder from a function of world x and y.
+    #[must_use]
+    pub fn new(sampler: impl Fn(f64, f64) -> f64 + Send + Sync + 'static) -> Self {
+        Self {
+            sampler: Arc::new(sampler),
+            region: SampleRegion::new(DVec2::ZERO, DVec2::splat(64.0)),
+            resolution: UVec2::splat(64),
+            levels: vec![0.5],
+            simplify: None,
+        }
+    }
+
+    /// Create a builder that samples `noise` with the given sampler.
+    #[must_use]
+    pub fn from_noise(noise: &Noise, mode: SampleMode) -> Self {
+        let noise = noise.clone();
+        Self::new(move |x, y| noise.sample(mode, x, y))
+    }
+
+    /// Set the region of world coordinates to trace.
+    #[must_use]
+    pub fn with_region(mut self, region: SampleRegion) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Set the number of grid cells per axis.
+    #[must_use]
+    pub fn with_resolution(mut self, resolution: UVec2) -> Self {
+        self.resolution = resolution.max(UVec2::ONE);
+        self
+    }
+
+    /// Trace a single iso level.
+    #[must_use]
+    pub fn with_level(mut self, level: f64) -> Self {
+        self.levels = vec![level];
+        self
+    }
+
+    /// Trace several iso levels.
+    #[must_use]
+    pub fn with_levels(mut self, levels: impl IntoIterator<Item = f64>) -> Self {
+        self.levels = levels.into_iter().collect();
+        self
+    }
+
+    /// Simplify extracted contours with the given tolerance. See
+    /// [`Contour::simplify`].
+    #[must_use]
+    pub fn with_simplify(mut self, tolerance: f64) -> Self {
+        self.simplify = Some(tolerance);
+        self
+    }
+
+    /// Trace all configured levels, in order.
+    #[must_use]
+    pub fn extract(&self) -> Vec<ContourLevel> {
+        let grid = self.sample_grid();
+        self.levels
+            .iter()
+            .map(|&level| ContourLevel {
+                level,
+                contours: self.trace(&grid, level),
+            })
+            .collect()
+    }
+
+    /// Trace a single level, ignoring the configured levels.
+    #[must_use]
+    pub fn extract_level(&self, level: f64) -> Vec<Contour> {
+        self.trace(&self.sample_grid(), level)
+    }
+
+    /// World position of grid point `point`.
+    fn position(&self, point: IVec2) -> DVec2 {
+        self.region.origin + point.as_dvec2() * self.region.size / self.resolution.as_dvec2()
+    }
+
+    /// Samples at grid points `0..=resolution`, row by row.
+    fn sample_grid(&self) -> Vec<f64> {
+        let points = self.resolution.as_ivec2() + 1;
+        let mut values = Vec::with_capacity((points.x * points.y) as usize);
+        for y in 0..points.y {
+            for x in 0..points.x {
+                let p = self.position(IVec2::new(x, y));
+                values.push((self.sampler)(p.x, p.y));
+            }
+        }
+        values
+    }
+
+    fn trace(&self, grid: &[f64], level: f64) -> Vec<Contour> {
+        let resolution = self.resolution.as_ivec2();
+        let value = |p: IVec2| grid[(p.x + p.y * (resolution.x + 1)) as usize];
+        let high = |p: IVec2| value(p) >= level;
+
+        // Grid edges are keyed by their lower point and axis (0 = x, 1 = y),
+        // so both cells sharing an edge agree on its crossing.
+        let edge_key = |a: IVec2, b: IVec2| {
+            let min = a.min(b);
+            (min.y, min.x, i32::from(a.x == b.x))
+        };
+        let crossing = |a: IVec2, b: IVec2| {
+            let (a, b) = if a.cmple(b).all() { (a, b) } else { (b, a) };
+            let (va, vb) = (value(a), value(b));
+            let t = if (vb - va).abs() > f64::EPSILON {
+                ((level - va) / (vb - va)).clamp(0.0, 1.0)
+            } else {
+                0.5
+            };
+            self.position(a).lerp(self.position(b), t)
+        };
+
+        // Each segment runs from the edge where the counter-clockwise walk
+        // around the cell leaves high ground to an edge where it re-enters,
+        // which keeps high ground on the segment's left.
+        let mut segments = BTreeMap::new();
+        let mut positions = BTreeMap::new();
+        for y in 0..resolution.y {
+            for x in 0..resolution.x {
+                let cell = IVec2::new(x, y);
+                let corners = CORNERS.map(|c| cell + c);
+                let crossings: Vec<(bool, (i32, i32, i32))> = (0..4)
+                    .filter_map(|i| {
+                        let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                        (high(a) != high(b)).then(|| {
+                            let key = edge_key(a, b);
+                            positions.entry(key).or_insert_with(|| crossing(a, b));
+                            (high(a), key)
+                        })
+                    })
+                    .collect();
+
+                // Saddles connect through the cell when its center is high
+                let center = corners.iter().map(|c| value(*c)).sum::<f64>() / 4.0;
+                let n = crossings.len();
+                for (i, &(leaves_high, start)) in crossings.iter().enumerate() {
+                    if !leaves_high {
+                        continue;
+                    }
+                    let partner = if center >= level { i + 1 } else { i + n - 1 };
+                    segments.insert(start, crossings[partner % n].1);
+                }
+            }
+        }
+
+        let mut contours = Vec::new();
+
+        // Open contours start on the region border at an edge no segment
+        // ends at.
+        let ends: BTreeSet<_> = segments.values().copied().collect();
+        let starts: Vec<_> = segments
+            .keys()
+            .filter(|key| !ends.contains(key))
+            .copied()
+            .collect();
+        for start in starts {
+            let mut points = vec![positions[&start]];
+            let mut key = start;
+            while let Some(next) = segments.remove(&key) {
+                points.push(positions[&next]);
+                key = next;
+            }
+            contours.push(Contour {
+                points,
+                closed: false,
+            });
+        }
+
+        // Every remaining segment belongs to a closed loop
+        while let Some((&start, _)) = segments.first_key_value() {
+            let mut points = Vec::new();
+            let mut key = start;
+            while let Some(next) = segments.remove(&key) {
+                points.push(positions[&key]);
+                key = next;
+            }
+            contours.push(Contour {
+                points,
+                closed: true,
+            });
+        }
+
+        match self.simplify {
+            Some(tolerance) => contours.iter().map(|c| c.simplify(tolerance)).collect(),
+            None => contours,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circle(radius: f64) -> ContourBuilder {
+        // Positive inside a circle centered in the region
+        ContourBuilder::new(move |x, y| radius - DVec2::new(x, y).distance(DVec2::splat(8.0)))
+            .with_region(SampleRegion::new(DVec2::ZERO, DVec2::splat(16.0)))
+            .with_resolution(UVec2::splat(16))
+            .with_level(0.0)
+    }
+
+    #[test]
+    fn circle_is_a_counter_clockwise_loop() {
+        let contours = circle(5.3).extract_level(0.0);
+
+        assert_eq!(contours.len(), 1);
+        let contour = &contours[0];
+        assert!(contour.closed);
+        assert!(contour.signed_area() > 0.0);
+        assert!((contour.signed_area() - std::f64::consts::PI * 5.3 * 5.3).abs() < 1.0);
+        for point in &contour.points {
+            assert!((point.distance(DVec2::splat(8.0)) - 5.3).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn hole_is_a_clockwise_loop() {
+        let contours = circle(5.3).extract_level(0.0);
+        let inverted =
+            ContourBuilder::new(|x, y| DVec2::new(x, y).distance(DVec2::splat(8.0)) - 5.3)
+                .with_region(SampleRegion::new(DVec2::ZERO, DVec2::splat(16.0)))
+                .with_resolution(UVec2::splat(16))
+                .extract_level(0.0);
+
+        assert_eq!(inverted.len(), 1);
+        assert!(inverted[0].closed);
+        assert!((inverted[0].signed_area() + contours[0].signed_area()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn simplify_keeps_corners() {
+        let square = Contour {
+            points: vec![
+                DVec2::new(0.0, 0.0),
+                DVec2::new(0.5, 0.01),
+                DVec2::new(1.0, 0.0),
+                DVec2::new(1.0, 1.0),
+                DVec2::new(0.0, 1.0),
+            ],
+            closed: true,
+        };
+
+        let simplified = square.simplify(0.1);
+        assert_eq!(simplified.points.len(), 4);
+        assert!(simplified.closed);
+        assert!((simplified.signed_area() - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,400 @@
+//! Divergence-free curl noise and baked flow fields.
+//!
+//! [`CurlNoise2D`] and [`CurlNoise3D`] take the curl of a noise potential,
+//! which yields swirling velocity fields without sources or sinks for
+//! particles, smoke and wind. [`FlowField`] bakes a 2D field into a grid
+//! for cheap lookups by many entities.
+//!
+//! With [`NoiseBackend::Deterministic`](crate::NoiseBackend::Deterministic)
+//! and the raw, normalized or fractal samplers, gradients are evaluated
+//! analytically. The Perlin generator does not expose derivatives, so
+//! other configurations use central differences in input coordinates.
+//! Both account for the noise's scale and input transform.
+
+use crate::{Noise, SampleMode, SampleRegion};
+#[cfg(feature = "bevy")]
+use bevy::prelude::Resource;
+use glam::{DVec2, DVec3, UVec2, Vec2};
+
+/// Default central difference distance in input coordinates.
+const DEFAULT_EPSILON: f64 = 0.01;
+
+/// Offsets separating the three potential components of 3D curl noise.
+const POTENTIAL_OFFSETS: [DVec3; 3] = [
+    DVec3::ZERO,
+    DVec3::new(123.4, -56.7, 89.1),
+    DVec3::new(-98.7, 65.4, -32.1),
+];
+
+/// 2D curl noise: the rotated gradient of a scalar noise potential.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{CurlNoise2D, Noise};
+///
+/// let wind = CurlNoise2D::new(Noise::new(12345).with_scale(0.01)).with_strength(4.0);
+/// let velocity = wind.velocity(10.0, 20.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CurlNoise2D {
+    noise: Noise,
+    mode: SampleMode,
+    strength: f64,
+    epsilon: f64,
+}
+
+impl CurlNoise2D {
+    /// Create curl noise using the raw values of `noise` as the potential.
+    #[must_use]
+    pub fn new(noise: Noise) -> Self {
+        Self {
+            noise,
+            mode: SampleMode::Raw,
+            strength: 1.0,
+            epsilon: DEFAULT_EPSILON,
+        }
+    }
+
+    /// Set the sampler used for the potential, e.g. a fractal mode for
+    /// more detailed flow.
+    #[must_use]
+    pub fn with_mode(mut self, mode: SampleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the multiplier applied to velocities.
+    #[must_use]
+    pub fn with_strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Set the central difference distance in input coordinates, used
+    /// when gradients are not analytic. Defaults to 0.01.
+    #[must_use]
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon.abs().max(f64::EPSILON);
+        self
+    }
+
+    /// Divergence-free velocity at a point.
+    #[must_use]
+    pub fn velocity(&self, x: f64, y: f64) -> DVec2 {
+        let analytic = self
+            .noise
+            .analytic_gradient::<2>(self.mode, DVec3::new(x, y, 0.0));
+        if let Some(gradient) = analytic {
+            return DVec2::new(gradient.y, -gradient.x) * self.strength;
+        }
+
+        let e = self.epsilon;
+        let potential = |x, y| self.noise.sample(self.mode, x, y);
+        let dx = potential(x + e, y) - potential(x - e, y);
+        let dy = potential(x, y + e) - potential(x, y - e);
+        DVec2::new(dy, -dx) * (self.strength / (2.0 * e))
+    }
+
+    /// Bake velocities over `region` into a [`FlowField`] with
+    /// `resolution` cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region size is not positive and finite on both axes.
+    #[must_use]
+    pub fn bake(&self, region: SampleRegion, resolution: UVec2) -> FlowField {
+        FlowField::bake(region, resolution, |p| self.velocity(p.x, p.y))
+    }
+}
+
+/// 3D curl noise: the curl of a vector potential built from three offset
+/// samples of a noise.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{CurlNoise3D, Noise};
+///
+/// let smoke = CurlNoise3D::new(Noise::new(12345).with_scale(0.05));
+/// let velocity = smoke.velocity(1.0, 2.0, 3.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CurlNoise3D {
+    noise: Noise,
+    mode: SampleMode,
+    strength: f64,
+    epsilon: f64,
+}
+
+impl CurlNoise3D {
+    /// Create curl noise using the raw values of `noise` as the potential.
+    #[must_use]
+    pub fn new(noise: Noise) -> Self {
+        Self {
+            noise,
+            mode: SampleMode::Raw,
+            strength: 1.0,
+            epsilon: DEFAULT_EPSILON,
+        }
+    }
+
+    /// Set the 3D sampler used for the potential.
+    #[must_use]
+    pub fn with_mode(mut self, mode: SampleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the multiplier applied to velocities.
+    #[must_use]
+    pub fn with_strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Set the central difference distance in input coordinates, used
+    /// when gradients are not analytic. Defaults to 0.01.
+    #[must_use]
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon.abs().max(f64::EPSILON);
+        self
+    }
+
+    /// Divergence-free velocity at a point.
+    #[must_use]
+    pub fn velocity(&self, x: f64, y: f64, z: f64) -> DVec3 {
+        let p = DVec3::new(x, y, z);
+        let analytic = POTENTIAL_OFFSETS
+            .map(|offset| self.noise.analytic_gradient::<3>(self.mode, p + offset));
+        if let [Some(g0), Some(g1), Some(g2)] = analytic {
+            return DVec3::new(g2.y - g1.z, g0.z - g2.x, g1.x - g0.y) * self.strength;
+        }
+
+        let e = self.epsilon;
+        let potential = |component: usize, p: DVec3| {
+            let p = p + POTENTIAL_OFFSETS[component];
+            self.noise.sample_3d(self.mode, p.x, p.y, p.z)
+        };
+        // d(component)/d(axis) by central differences
+        let derivative = |component: usize, axis: DVec3| {
+            potential(component, p + axis * e) - potential(component, p - axis * e)
+        };
+
+        DVec3::new(
+            derivative(2, DVec3::Y) - derivative(1, DVec3::Z),
+            derivative(0, DVec3::Z) - derivative(2, DVec3::X),
+            derivative(1, DVec3::X) - derivative(0, DVec3::Y),
+        ) * (self.strength / (2.0 * e))
+    }
+}
+
+/// A 2D vector field baked into a grid for cheap lookups.
+///
+/// Vectors are stored at cell centers and bilinearly interpolated.
+/// Positions outside the region are clamped to its border.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::math::{DVec2, UVec2, Vec2};
+/// use msg_noise::{CurlNoise2D, Noise, SampleRegion};
+///
+/// let wind = CurlNoise2D::new(Noise::new(12345).with_scale(0.01));
+/// let field = wind.bake(SampleRegion::new(DVec2::ZERO, DVec2::splat(256.0)), UVec2::splat(64));
+///
+/// // Per frame, for every particle
+/// let velocity = field.sample(Vec2::new(100.0, 42.0));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct FlowField {
+    region: SampleRegion,
+    resolution: UVec2,
+    vectors: Vec<Vec2>,
+}
+
+impl FlowField {
+    /// Bake `f` at the center of each cell of `region`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region size is not positive and finite on both axes.
+    #[must_use]
+    pub fn bake(region: SampleRegion, resolution: UVec2, f: impl Fn(DVec2) -> DVec2) -> Self {
+        assert!(
+            region.size.is_finite() && region.size.cmpgt(DVec2::ZERO).all(),
+            "flow field region size must be positive and finite: {:?}",
+            region.size
+        );
+        let resolution = resolution.max(UVec2::ONE);
+        let mut vectors = Vec::with_capacity((resolution.x * resolution.y) as usize);
+        for y in 0..resolution.y {
+            for x in 0..resolution.x {
+                vectors.push(f(region.cell_center(UVec2::new(x, y), resolution)).as_vec2());
+            }
+        }
+        Self {
+            region,
+            resolution,
+            vectors,
+        }
+    }
+
+    /// Get the baked region.
+    #[must_use]
+    pub fn region(&self) -> SampleRegion {
+        self.region
+    }
+
+    /// Get the number of cells per axis.
+    #[must_use]
+    pub fn resolution(&self) -> UVec2 {
+        self.resolution
+    }
+
+    /// Baked vectors row by row.
+    #[must_use]
+    pub fn vectors(&self) -> &[Vec2] {
+        &self.vectors
+    }
+
+    /// Vector stored for `cell`.
+    #[must_use]
+    pub fn get(&self, cell: UVec2) -> Vec2 {
+        let cell = cell.min(self.resolution - 1);
+        self.vectors[(cell.y * self.resolution.x + cell.x) as usize]
+    }
+
+    /// Bilinearly interpolated vector at `position`.
+    #[must_use]
+    pub fn sample(&self, position: Vec2) -> Vec2 {
+        let max = (self.resolution - 1).as_vec2();
+        let cell_size = (self.region.size / self.resolution.as_dvec2()).as_vec2();
+        let local =
+            ((position - self.region.origin.as_vec2()) / cell_size - 0.5).clamp(Vec2::ZERO, max);
+
+        let base = local.floor().as_uvec2();
+        let t = local - base.as_vec2();
+        let a = self.get(base).lerp(self.get(base + UVec2::X), t.x);
+        let b = self
+            .get(base + UVec2::Y)
+            .lerp(self.get(base + UVec2::ONE), t.x);
+        a.lerp(b, t.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curl_2d_is_divergence_free() {
+        let curl = CurlNoise2D::new(Noise::new(7).with_scale(0.05));
+        let h = 0.05;
+
+        for i in 0..20 {
+            let (x, y) = (f64::from(i) * 3.1, f64::from(i) * 1.7);
+            let divergence = (curl.velocity(x + h, y).x - curl.velocity(x - h, y).x
+                + curl.velocity(x, y + h).y
+                - curl.velocity(x, y - h).y)
+                / (2.0 * h);
+            let magnitude = curl.velocity(x, y).length().max(1e-3);
+            assert!(divergence.abs() / magnitude < 0.05);
+        }
+    }
+
+    #[test]
+    fn curl_3d_is_divergence_free() {
+        let curl = CurlNoise3D::new(Noise::new(7).with_scale(0.05));
+        let h = 0.05;
+
+        for i in 0..20 {
+            let p = DVec3::new(f64::from(i) * 3.1, f64::from(i) * 1.7, f64::from(i) * 0.9);
+            let v = |offset: DVec3| {
+                let q = p + offset;
+                curl.velocity(q.x, q.y, q.z)
+            };
+            let divergence = (v(DVec3::X * h).x - v(-DVec3::X * h).x + v(DVec3::Y * h).y
+                - v(-DVec3::Y * h).y
+                + v(DVec3::Z * h).z
+                - v(-DVec3::Z * h).z)
+                / (2.0 * h);
+            let magnitude = v(DVec3::ZERO).length().max(1e-3);
+            assert!(divergence.abs() / magnitude < 0.05);
+        }
+    }
+
+    #[test]
+    fn analytic_velocity_matches_differences() {
+        use crate::NoiseBackend;
+        use glam::{DAffine3, DMat3, DQuat};
+
+        let shear = DMat3::from_cols(DVec3::X, DVec3::new(0.3, 1.0, 0.0), DVec3::Z);
+        let noise = Noise::new(7)
+            .with_backend(NoiseBackend::Deterministic)
+            .with_axis_scale(DVec3::new(0.05, 0.025, 0.1))
+            .with_rotation(DQuat::from_rotation_z(0.4))
+            .with_transform(DAffine3::from_mat3(shear));
+        let fractal = SampleMode::Fractal {
+            octaves: 3,
+            persistence: 0.5,
+            lacunarity: 2.0,
+        };
+        let h = 1e-5;
+
+        for mode in [SampleMode::Raw, fractal] {
+            let curl_2d = CurlNoise2D::new(noise.clone()).with_mode(mode);
+            let curl_3d = CurlNoise3D::new(noise.clone()).with_mode(mode);
+            let potential = |p: DVec3| noise.sample_3d(mode, p.x, p.y, p.z);
+            let derivative = |p: DVec3, axis: DVec3| {
+                (potential(p + axis * h) - potential(p - axis * h)) / (2.0 * h)
+            };
+
+            for i in 0..10 {
+                let p = DVec3::new(f64::from(i) * 3.1, f64::from(i) * 1.7, f64::from(i) * 0.9);
+
+                let dx = noise.sample(mode, p.x + h, p.y) - noise.sample(mode, p.x - h, p.y);
+                let dy = noise.sample(mode, p.x, p.y + h) - noise.sample(mode, p.x, p.y - h);
+                let expected = DVec2::new(dy, -dx) / (2.0 * h);
+                assert!(curl_2d.velocity(p.x, p.y).distance(expected) < 1e-4);
+
+                let [p0, p1, p2] = POTENTIAL_OFFSETS.map(|offset| p + offset);
+                let expected = DVec3::new(
+                    derivative(p2, DVec3::Y) - derivative(p1, DVec3::Z),
+                    derivative(p0, DVec3::Z) - derivative(p2, DVec3::X),
+                    derivative(p1, DVec3::X) - derivative(p0, DVec3::Y),
+                );
+                assert!(curl_3d.velocity(p.x, p.y, p.z).distance(expected) < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "positive and finite")]
+    fn flow_field_rejects_empty_region() {
+        let _ = FlowField::bake(
+            SampleRegion::new(DVec2::ZERO, DVec2::new(4.0, 0.0)),
+            UVec2::splat(4),
+            |p| p,
+        );
+    }
+
+    #[test]
+    fn flow_field_interpolates_cell_centers() {
+        let region = SampleRegion::new(DVec2::ZERO, DVec2::splat(4.0));
+        let field = FlowField::bake(region, UVec2::splat(4), |p| p);
+
+        // Cell centers store their own position
+        assert_eq!(field.sample(Vec2::new(0.5, 2.5)), Vec2::new(0.5, 2.5));
+        // Linear fields interpolate exactly between centers
+        assert!(
+            field
+                .sample(Vec2::new(1.25, 3.0))
+                .distance(Vec2::new(1.25, 3.0))
+                < 1e-6
+        );
+        // Outside the region the border value is held
+        assert_eq!(field.sample(Vec2::new(-10.0, 100.0)), Vec2::new(0.5, 3.5));
+    }
+}
@@ -0,0 +1,231 @@
+//! Arbitrary `noise` crate generators behind the [`Noise`] settings.
+//!
+//! [`CustomNoise`] evaluates any [`NoiseFn`] generator, such as `Billow`,
+//! `RidgedMulti` or `Worley`, through the input transform, range, curve and
+//! clamping of a [`Noise`]. Seedable generators are seeded with the same
+//! key and salt derivation as [`NoiseSource::create`].
+
+use crate::{LINE_Y, Noise, NoiseSampler, NoiseSource};
+use glam::DVec3;
+use noise::{NoiseFn, Seedable};
+
+/// A `noise` crate generator sampled with the settings of a [`Noise`].
+///
+/// The wrapped [`Noise`] supplies the input transform, output range, remap
+/// curve and clamping; its own Perlin generator and backend are unused.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::NoiseSource;
+/// use noise::{Perlin, RidgedMulti, Worley};
+///
+/// let source = NoiseSource::new(12345);
+///
+/// // Seeded like `source.create(0x5249_4447)`
+/// let ridges = source.create_custom::<RidgedMulti<Perlin>>(0x5249_4447);
+/// let value = ridges.get_raw(10.0, 20.0);
+///
+/// // Configure the settings first, then attach a generator
+/// let cells = source
+///     .create_salted(0x4345_4C4C, 1)
+///     .with_scale(0.05)
+///     .with_range(0.0, 10.0)
+///     .with_generator(Worley::default());
+/// let cell = cells.get_scaled(10.0, 20.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CustomNoise<G> {
+    generator: G,
+    noise: Noise,
+}
+
+impl<G> CustomNoise<G> {
+    /// Wrap `generator` with the settings of `noise`.
+    ///
+    /// The generator is used as is, so this also works for generators
+    /// without a seed such as `Checkerboard`. Use [`Noise::with_generator`]
+    /// to seed the generator from the noise instead.
+    #[must_use]
+    pub fn new(generator: G, noise: Noise) -> Self {
+        Self { generator, noise }
+    }
+
+    /// Get the wrapped generator.
+    #[must_use]
+    pub fn generator(&self) -> &G {
+        &self.generator
+    }
+
+    /// Get the wrapped generator mutably.
+    pub fn generator_mut(&mut self) -> &mut G {
+        &mut self.generator
+    }
+
+    /// Get the noise providing the transform and range settings.
+    #[must_use]
+    pub fn noise(&self) -> &Noise {
+        &self.noise
+    }
+
+    /// Get the noise providing the transform and range settings mutably.
+    pub fn noise_mut(&mut self) -> &mut Noise {
+        &mut self.noise
+    }
+}
+
+impl<G: NoiseFn<f64, 2> + NoiseFn<f64, 3>> CustomNoise<G> {
+    /// Get the raw generator value at `(x, y)`.
+    ///
+    /// The range depends on the generator; most return values between
+    /// -1.0 and 1.0.
+    #[must_use]
+    pub fn get_raw(&self, x: f64, y: f64) -> f64 {
+        let point = self.noise.to_noise_space(DVec3::new(x, y, 0.0));
+        NoiseFn::<f64, 2>::get(&self.generator, [point.x, point.y])
+    }
+
+    /// Get the raw 1D generator value along the same line as
+    /// [`Noise::get_raw_1d`].
+    #[must_use]
+    pub fn get_raw_1d(&self, t: f64) -> f64 {
+        let point = self.noise.to_noise_space(DVec3::new(t, 0.0, 0.0));
+        NoiseFn::<f64, 2>::get(&self.generator, [point.x, point.y + LINE_Y])
+    }
+
+    /// Get the raw 3D generator value at `(x, y, z)`.
+    #[must_use]
+    pub fn get_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        let point = self.noise.to_noise_space(DVec3::new(x, y, z));
+        NoiseFn::<f64, 3>::get(&self.generator, point.to_array())
+    }
+
+    /// Get the normalized value, assuming the generator returns values
+    /// between -1.0 and 1.0.
+    #[must_use]
+    pub fn get_normalized(&self, x: f64, y: f64) -> f64 {
+        (self.get_raw(x, y) + 1.0) * 0.5
+    }
+
+    /// Get the normalized 3D value, assuming the generator returns values
+    /// between -1.0 and 1.0.
+    #[must_use]
+    pub fn get_normalized_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        (self.get_raw_3d(x, y, z) + 1.0) * 0.5
+    }
+
+    /// Get the value scaled to the configured range.
+    #[must_use]
+    pub fn get_scaled(&self, x: f64, y: f64) -> f64 {
+        self.noise.map_to_range(self.get_normalized(x, y))
+    }
+
+    /// Get the 3D value scaled to the configured range.
+    #[must_use]
+    pub fn get_scaled_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.noise.map_to_range(self.get_normalized_3d(x, y, z))
+    }
+}
+
+impl<G: NoiseFn<f64, 2> + NoiseFn<f64, 3>> NoiseSampler for CustomNoise<G> {
+    #[inline]
+    fn sample_raw(&self, x: f64, y: f64) -> f64 {
+        self.get_raw(x, y)
+    }
+
+    #[inline]
+    fn sample_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.get_raw_3d(x, y, z)
+    }
+}
+
+impl<G: NoiseFn<f64, 2>> NoiseFn<f64, 2> for CustomNoise<G> {
+    #[inline]
+    fn get(&self, point: [f64; 2]) -> f64 {
+        let point = self
+            .noise
+            .to_noise_space(DVec3::new(point[0], point[1], 0.0));
+        self.generator.get([point.x, point.y])
+    }
+}
+
+impl<G: NoiseFn<f64, 3>> NoiseFn<f64, 3> for CustomNoise<G> {
+    #[inline]
+    fn get(&self, point: [f64; 3]) -> f64 {
+        let point = self.noise.to_noise_space(DVec3::from_array(point));
+        self.generator.get(point.to_array())
+    }
+}
+
+impl Noise {
+    /// Sample `generator` with this noise's settings, seeding it with this
+    /// noise's seed.
+    ///
+    /// Noise created by [`NoiseSource::create`] or
+    /// [`NoiseSource::create_salted`] carries the derived seed, so the
+    /// generator follows the same key and salt derivation.
+    #[must_use]
+    pub fn with_generator<G: Seedable>(self, generator: G) -> CustomNoise<G> {
+        let generator = generator.set_seed(self.seed);
+        CustomNoise::new(generator, self)
+    }
+}
+
+impl NoiseSource {
+    /// Create a default `noise` crate generator with a derived seed.
+    ///
+    /// Uses the same key derivation as [`Self::create`]. Configure the
+    /// generator or settings through [`CustomNoise::generator_mut`] and
+    /// [`CustomNoise::noise_mut`], or use [`Noise::with_generator`] to
+    /// wrap a configured generator.
+    #[must_use]
+    pub fn create_custom<G: Seedable + Default>(&self, key: u32) -> CustomNoise<G> {
+        self.create(key).with_generator(G::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use noise::{Billow, Checkerboard, Perlin, Worley};
+
+    #[test]
+    fn generators_follow_key_derivation() {
+        let source = NoiseSource::new(42);
+        let worley = source.create_custom::<Worley>(7);
+        assert_eq!(worley.generator().seed(), source.create(7).seed);
+
+        let salted = source
+            .create_salted(7, 3)
+            .with_generator(Billow::<Perlin>::default());
+        assert_eq!(salted.generator().seed(), source.create_salted(7, 3).seed);
+
+        let other = source.create_custom::<Worley>(8);
+        assert_ne!(worley.get_raw(0.3, 0.7), other.get_raw(0.3, 0.7));
+    }
+
+    #[test]
+    fn settings_are_applied() {
+        let noise = Noise::new(5)
+            .with_scale(0.1)
+            .with_axis_offset(DVec3::new(2.0, 3.0, 4.0))
+            .with_range(-10.0, 10.0);
+        let custom = noise.clone().with_generator(Perlin::default());
+        for i in 0..20 {
+            let t = f64::from(i) * 1.3;
+            assert_eq!(custom.get_raw(t, -t), noise.get_raw(t, -t));
+            assert_eq!(
+                custom.get_scaled_3d(t, -t, t),
+                noise.get_scaled_3d(t, -t, t)
+            );
+            assert_eq!(custom.get_raw_1d(t), noise.get_raw_1d(t));
+        }
+    }
+
+    #[test]
+    fn unseeded_generators_can_be_wrapped() {
+        let board = CustomNoise::new(Checkerboard::default(), Noise::new(0).with_scale(1.0));
+        assert_eq!(board.get_raw(0.5, 0.5), -board.get_raw(1.5, 0.5));
+        assert_eq!(NoiseFn::get(&board, [0.5, 0.5]), board.get_raw(0.5, 0.5));
+    }
+}
@@ -0,0 +1,269 @@
+//! Bit-exact gradient noise for lockstep simulations.
+//!
+//! The [`NoiseBackend::Deterministic`] backend evaluates gradient noise
+//! using only integer hashing and IEEE 754 addition, subtraction and
+//! multiplication in a fixed order. Those operations are correctly rounded
+//! on every conforming target, and Rust never contracts them into fused
+//! multiply-adds, so results are identical across platforms, optimization
+//! levels and `std`/`no_std` builds. No `libm` or platform math functions
+//! are involved.
+
+/// Algorithm used to evaluate a [`Noise`](crate::Noise).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NoiseBackend {
+    /// Perlin noise from the `noise` crate. Reproducible on a given
+    /// platform, but not guaranteed to be bit-exact across targets or
+    /// `noise` versions.
+    #[default]
+    Perlin,
+    /// Bit-exact gradient noise, identical on every platform.
+    ///
+    /// Values differ from [`NoiseBackend::Perlin`] but have the same
+    /// character and range. Raw, normalized, absolute and fractal samplers
+    /// are bit-exact, as is mapping to a range with the linear, smoothstep
+    /// and spline curves. Power curves and calibration use platform math
+    /// and are not covered.
+    Deterministic,
+}
+
+/// Output scale for 4D noise, whose longer gradients otherwise reach
+/// beyond -1.0 and 1.0. 2D and 3D noise already span roughly that range.
+const SCALE_4D: f64 = 0.85;
+
+/// Evaluate deterministic gradient noise at a point in noise space.
+pub(crate) fn gradient_noise<const DIM: usize>(seed: u32, point: [f64; DIM]) -> f64 {
+    let mut cell = [0_i64; DIM];
+    let mut local = [0.0; DIM];
+    let mut fade = [0.0; DIM];
+    for axis in 0..DIM {
+        cell[axis] = floor(point[axis]);
+        local[axis] = point[axis] - cell[axis] as f64;
+        fade[axis] = quintic(local[axis]);
+    }
+
+    // Dot products at the 2^DIM cell corners, then interpolate one axis
+    // at a time, halving the corner list each step
+    let mut corners = [0.0; 16];
+    for (corner, value) in corners.iter_mut().enumerate().take(1 << DIM) {
+        let mut hash = u64::from(seed);
+        let mut offset = [0.0; DIM];
+        for axis in 0..DIM {
+            let bit = (corner >> axis) & 1;
+            hash = mix(hash ^ (cell[axis] + bit as i64) as u64);
+            offset[axis] = local[axis] - bit as f64;
+        }
+        *value = gradient_dot(hash, &offset);
+    }
+    let mut count = 1 << DIM;
+    for t in fade {
+        count /= 2;
+        for i in 0..count {
+            let (a, b) = (corners[2 * i], corners[2 * i + 1]);
+            corners[i] = a + t * (b - a);
+        }
+    }
+
+    let scale = if DIM == 4 { SCALE_4D } else { 1.0 };
+    (corners[0] * scale).clamp(-1.0, 1.0)
+}
+
+/// Analytic gradient of [`gradient_noise`] with respect to `point`.
+///
+/// Uses the same corners and fade curve, but is not bit-exact. Zero where
+/// the output is clamped.
+#[cfg(feature = "std")]
+pub(crate) fn gradient_noise_gradient<const DIM: usize>(
+    seed: u32,
+    point: [f64; DIM],
+) -> [f64; DIM] {
+    let mut cell = [0_i64; DIM];
+    let mut local = [0.0; DIM];
+    let mut fade = [0.0; DIM];
+    let mut fade_slope = [0.0; DIM];
+    for axis in 0..DIM {
+        cell[axis] = floor(point[axis]);
+        local[axis] = point[axis] - cell[axis] as f64;
+        fade[axis] = quintic(local[axis]);
+        fade_slope[axis] = quintic_slope(local[axis]);
+    }
+
+    // value = sum of corner weights times corner dot products, where each
+    // weight is a product of fade factors
+    let mut value = 0.0;
+    let mut gradient = [0.0; DIM];
+    for corner in 0..1 << DIM {
+        let mut hash = u64::from(seed);
+        let mut offset = [0.0; DIM];
+        let mut factors = [0.0; DIM];
+        let mut slopes = [0.0; DIM];
+        for axis in 0..DIM {
+            let bit = (corner >> axis) & 1;
+            hash = mix(hash ^ (cell[axis] + bit as i64) as u64);
+            offset[axis] = local[axis] - bit as f64;
+            (factors[axis], slopes[axis]) = if bit == 1 {
+                (fade[axis], fade_slope[axis])
+            } else {
+                (1.0 - fade[axis], -fade_slope[axis])
+            };
+        }
+        let dot = gradient_dot(hash, &offset);
+        let corner_gradient = corner_gradient::<DIM>(hash);
+        let weight: f64 = factors.iter().product();
+        value += weight * dot;
+        for axis in 0..DIM {
+            let others: f64 = (0..DIM)
+                .filter(|&other| other != axis)
+                .map(|other| factors[other])
+                .product();
+            gradient[axis] += others * slopes[axis] * dot + weight * corner_gradient[axis];
+        }
+    }
+
+    let scale = if DIM == 4 { SCALE_4D } else { 1.0 };
+    if (value * scale).abs() > 1.0 {
+        return [0.0; DIM];
+    }
+    gradient.map(|component| component * scale)
+}
+
+/// Components of the hashed corner gradient used by [`gradient_dot`].
+#[cfg(feature = "std")]
+fn corner_gradient<const DIM: usize>(hash: u64) -> [f64; DIM] {
+    let zeroed = if DIM > 2 {
+        ((hash >> 32) % DIM as u64) as usize
+    } else {
+        DIM
+    };
+    core::array::from_fn(|axis| {
+        if axis == zeroed {
+            0.0
+        } else if (hash >> axis) & 1 == 0 {
+            1.0
+        } else {
+            -1.0
+        }
+    })
+}
+
+/// Dot product of the hashed corner gradient with `offset`.
+///
+/// Gradients have components of -1, 0 or 1: diagonals in 2D, and in
+/// higher dimensions the midpoints of the hypercube's edges (one axis
+/// zeroed), as in improved Perlin noise.
+fn gradient_dot<const DIM: usize>(hash: u64, offset: &[f64; DIM]) -> f64 {
+    let zeroed = if DIM > 2 {
+        ((hash >> 32) % DIM as u64) as usize
+    } else {
+        DIM
+    };
+    let mut sum = 0.0;
+    for (axis, &component) in offset.iter().enumerate() {
+        if axis == zeroed {
+            continue;
+        }
+        if (hash >> axis) & 1 == 0 {
+            sum += component;
+        } else {
+            sum -= component;
+        }
+    }
+    sum
+}
+
+/// Floor as an integer, without relying on platform rounding functions.
+/// Non-finite inputs map to zero.
+#[inline]
+fn floor(x: f64) -> i64 {
+    if !x.is_finite() {
+        return 0;
+    }
+    let truncated = x as i64;
+    if (truncated as f64) > x {
+        truncated - 1
+    } else {
+        truncated
+    }
+}
+
+/// Improved Perlin fade curve, `6t⁵ - 15t⁴ + 10t³`.
+#[inline]
+fn quintic(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Derivative of [`quintic`], `30t²(t - 1)²`.
+#[cfg(feature = "std")]
+#[inline]
+fn quintic_slope(t: f64) -> f64 {
+    let u = t * (t - 1.0);
+    30.0 * u * u
+}
+
+/// `SplitMix64` finalizer.
+#[inline]
+pub(crate) fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lattice_points_are_zero() {
+        for i in -5..5 {
+            let x = f64::from(i);
+            assert_eq!(gradient_noise(7, [x, 2.0]), 0.0);
+            assert_eq!(gradient_noise(7, [x, -3.0, 1.0]), 0.0);
+        }
+    }
+
+    #[test]
+    fn values_are_continuous_across_cells() {
+        for i in 0..20 {
+            let x = f64::from(i) * 0.37 + 1.0;
+            let below = gradient_noise(3, [1.0 - 1e-9, x, x * 0.5]);
+            let above = gradient_noise(3, [1.0 + 1e-9, x, x * 0.5]);
+            assert!((below - above).abs() < 1e-6);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn analytic_gradient_matches_differences() {
+        let h = 1e-6;
+        for i in 0..50 {
+            let t = f64::from(i) * 0.173 - 3.0;
+            let point = [t, t * 0.61 + 0.2, t * -0.27];
+            let gradient = gradient_noise_gradient(5, point);
+            for axis in 0..3 {
+                let (mut above, mut below) = (point, point);
+                above[axis] += h;
+                below[axis] -= h;
+                let expected = (gradient_noise(5, above) - gradient_noise(5, below)) / (2.0 * h);
+                assert!((gradient[axis] - expected).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn output_stays_in_range_and_uses_it() {
+        let (mut min, mut max) = (f64::MAX, f64::MIN);
+        for i in 0..20_000 {
+            let t = f64::from(i) * 0.0731;
+            for value in [
+                gradient_noise(11, [t, t * 0.61]),
+                gradient_noise(11, [t, t * 0.61, t * 0.27]),
+                gradient_noise(11, [t, t * 0.61, t * 0.27, t * 0.13]),
+            ] {
+                min = min.min(value);
+                max = max.max(value);
+            }
+        }
+        assert!((-1.0..-0.5).contains(&min));
+        assert!((0.5..=1.0).contains(&max));
+    }
+}
@@ -0,0 +1,270 @@
+//! Bevy diagnostics for noise evaluation.
+//!
+//! [`NoiseDiagnosticsPlugin`] reports how many noise samples were evaluated
+//! each frame and how long image, statistics and mesh builds took.
+
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+/// Number of enabled [`NoiseDiagnosticsRecorder`]s. Nothing is recorded
+/// while it is zero.
+static RECORDERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counters of every thread that recorded.
+static THREADS: Mutex<Threads> = Mutex::new(Threads {
+    live: Vec::new(),
+    retired: Totals {
+        samples: 0,
+        nanos: 0,
+    },
+});
+
+thread_local! {
+    static COUNTS: Arc<ThreadCounts> = {
+        let counts = Arc::<ThreadCounts>::default();
+        lock_threads().live.push(Arc::clone(&counts));
+        counts
+    };
+}
+
+/// Counters written only by their own thread, so recording never contends
+/// with other threads.
+#[derive(Default)]
+struct ThreadCounts {
+    samples: AtomicU64,
+    nanos: AtomicU64,
+}
+
+impl ThreadCounts {
+    fn add(counter: &AtomicU64, value: u64) {
+        // Single writer: a plain load and store is enough
+        counter.store(
+            counter.load(Ordering::Relaxed).wrapping_add(value),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn totals(&self) -> Totals {
+        Totals {
+            samples: self.samples.load(Ordering::Relaxed),
+            nanos: self.nanos.load(Ordering::Relaxed),
+        }
+    }
+}
+
+struct Threads {
+    live: Vec<Arc<ThreadCounts>>,
+    /// Counts of threads that have exited.
+    retired: Totals,
+}
+
+/// Samples and nanoseconds recorded since the process started.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Totals {
+    samples: u64,
+    nanos: u64,
+}
+
+impl Totals {
+    fn add(self, other: Self) -> Self {
+        Self {
+            samples: self.samples.wrapping_add(other.samples),
+            nanos: self.nanos.wrapping_add(other.nanos),
+        }
+    }
+
+    fn since(self, earlier: Self) -> Self {
+        Self {
+            samples: self.samples.wrapping_sub(earlier.samples),
+            nanos: self.nanos.wrapping_sub(earlier.nanos),
+        }
+    }
+}
+
+fn lock_threads() -> std::sync::MutexGuard<'static, Threads> {
+    THREADS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sum the counters of all threads, folding exited threads into the
+/// retired total.
+fn totals() -> Totals {
+    let mut threads = lock_threads();
+    let Threads { live, retired } = &mut *threads;
+    live.retain(|counts| {
+        if Arc::strong_count(counts) > 1 {
+            return true;
+        }
+        *retired = retired.add(counts.totals());
+        false
+    });
+    live.iter()
+        .fold(*retired, |sum, counts| sum.add(counts.totals()))
+}
+
+/// Plugin that reports noise evaluation diagnostics.
+///
+/// Once added, every raw noise evaluation is counted, and noise images,
+/// statistics and meshes are timed as a whole. Single samples are not
+/// timed, so sampling stays cheap. Each thread counts into its own
+/// counters, which the app sums once per frame.
+///
+/// Noise has no link to the app sampling it, so each app reports the
+/// samples of the whole process since its own previous frame. Apps do not
+/// reset each other's measurements. Use the [`NoiseDiagnosticsRecorder`]
+/// resource to stop recording.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_noise::{NoiseDiagnosticsPlugin, NoiseDiagnosticsRecorder, NoisePlugin};
+///
+/// let mut app = App::new();
+/// app.add_plugins(NoisePlugin::seeded(12345))
+///     .add_plugins(NoiseDiagnosticsPlugin);
+///
+/// // Stop recording, e.g. after profiling
+/// app.world_mut()
+///     .resource_mut::<NoiseDiagnosticsRecorder>()
+///     .set_enabled(false);
+/// ```
+pub struct NoiseDiagnosticsPlugin;
+
+impl NoiseDiagnosticsPlugin {
+    /// Number of noise samples evaluated per frame.
+    pub const SAMPLES: DiagnosticPath = DiagnosticPath::const_new("noise/samples");
+    /// Time spent building noise images, statistics and meshes per frame,
+    /// in milliseconds.
+    pub const EVALUATION_TIME: DiagnosticPath = DiagnosticPath::const_new("noise/evaluation_time");
+}
+
+impl Plugin for NoiseDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(NoiseDiagnosticsRecorder::new())
+            .register_diagnostic(Diagnostic::new(Self::SAMPLES).with_suffix(" samples"))
+            .register_diagnostic(Diagnostic::new(Self::EVALUATION_TIME).with_suffix("ms"))
+            .add_systems(Last, update_noise_diagnostics);
+    }
+}
+
+/// Recording state of an app's noise diagnostics, inserted by
+/// [`NoiseDiagnosticsPlugin`].
+///
+/// Samples and build times are recorded while any app in the process has
+/// an enabled recorder. A disabled recorder reports zero.
+#[derive(Resource, Debug)]
+pub struct NoiseDiagnosticsRecorder {
+    enabled: bool,
+    last: Totals,
+}
+
+impl NoiseDiagnosticsRecorder {
+    fn new() -> Self {
+        RECORDERS.fetch_add(1, Ordering::Relaxed);
+        Self {
+            enabled: true,
+            last: totals(),
+        }
+    }
+
+    /// Start or stop recording for this app.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled != self.enabled {
+            if enabled {
+                RECORDERS.fetch_add(1, Ordering::Relaxed);
+            } else {
+                RECORDERS.fetch_sub(1, Ordering::Relaxed);
+            }
+            self.enabled = enabled;
+        }
+    }
+
+    /// Whether this app records samples and build times.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Samples and nanoseconds recorded since the last call.
+    fn take(&mut self) -> Totals {
+        let now = totals();
+        let recorded = now.since(self.last);
+        self.last = now;
+        if self.enabled {
+            recorded
+        } else {
+            Totals::default()
+        }
+    }
+}
+
+impl Drop for NoiseDiagnosticsRecorder {
+    fn drop(&mut self) {
+        self.set_enabled(false);
+    }
+}
+
+/// Count a single noise evaluation when recording.
+#[inline]
+pub(crate) fn record_sample() {
+    if RECORDERS.load(Ordering::Relaxed) > 0 {
+        // Ignore samples taken while the thread is shutting down
+        let _ = COUNTS.try_with(|counts| ThreadCounts::add(&counts.samples, 1));
+    }
+}
+
+/// Run `f`, adding its duration to the evaluation time when recording.
+pub(crate) fn timed<T>(f: impl FnOnce() -> T) -> T {
+    if RECORDERS.load(Ordering::Relaxed) == 0 {
+        return f();
+    }
+    let start = Instant::now();
+    let value = f();
+    let nanos = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+    let _ = COUNTS.try_with(|counts| ThreadCounts::add(&counts.nanos, nanos));
+    value
+}
+
+fn update_noise_diagnostics(
+    mut recorder: ResMut<NoiseDiagnosticsRecorder>,
+    mut diagnostics: Diagnostics,
+) {
+    let recorded = recorder.take();
+
+    diagnostics.add_measurement(&NoiseDiagnosticsPlugin::SAMPLES, || recorded.samples as f64);
+    diagnostics.add_measurement(&NoiseDiagnosticsPlugin::EVALUATION_TIME, || {
+        recorded.nanos as f64 / 1_000_000.0
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Noise;
+
+    #[test]
+    fn recorders_measure_independently() {
+        let noise = Noise::new(1);
+        let thread_samples = || COUNTS.with(|counts| counts.totals().samples);
+        let mut first = NoiseDiagnosticsRecorder::new();
+        let mut second = NoiseDiagnosticsRecorder::new();
+
+        let before = thread_samples();
+        for i in 0..10 {
+            let _ = noise.get_raw(f64::from(i), 0.0);
+        }
+        assert_eq!(thread_samples(), before + 10);
+
+        // Other threads may record concurrently, so only lower bounds hold
+        assert!(first.take().samples >= 10);
+        assert!(second.take().samples >= 10, "reading must not reset others");
+
+        second.set_enabled(false);
+        let _ = noise.get_raw(0.5, 0.5);
+        assert_eq!(second.take(), Totals::default());
+        assert!(first.is_enabled() && !second.is_enabled());
+    }
+}
@@ -0,0 +1,365 @@
+//! Integer-only noise for simulation logic.
+//!
+//! [`FixedNoise`] evaluates gradient or value noise entirely in integer
+//! arithmetic and returns Q16.16 fixed-point values, so gameplay systems
+//! in a fixed-timestep simulation never touch floating point.
+
+use crate::deterministic::mix;
+use crate::{NOISE_OUTPUT_VERSION, UnsupportedOutputVersion};
+
+/// Number of fractional bits in [`FixedNoise`] values.
+const FRACTION_BITS: u32 = 16;
+
+/// 1.0 in Q16.16 as a 64-bit intermediate.
+const ONE: i64 = 1 << FRACTION_BITS;
+
+/// Default lattice spacing in input units.
+const DEFAULT_CELL_SIZE: u32 = 128;
+
+/// Interpolation scheme of [`FixedNoise`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FixedNoiseKind {
+    /// Gradient noise with hashed corner gradients, like Perlin noise.
+    #[default]
+    Gradient,
+    /// Value noise interpolating hashed corner values. Blockier than
+    /// gradient noise but cheaper.
+    Value,
+}
+
+/// Noise evaluated with integer arithmetic only.
+///
+/// Inputs are integer coordinates, e.g. tiles, and the lattice repeats
+/// every `cell_size` units. Outputs are Q16.16 fixed-point values where
+/// [`FixedNoise::ONE`] represents 1.0. Results are identical on every
+/// platform, and stay the same across releases for a given
+/// [`output_version`](Self::output_version).
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{FixedNoise, NoiseSource};
+///
+/// let source = NoiseSource::new(12345);
+/// let richness = source
+///     .create_fixed(0x5249_4348)
+///     .with_cell_size(32)
+///     .with_range(0, 100);
+///
+/// // Ore per tile, between 0 and 100
+/// let ore = richness.get_scaled(17, -4);
+/// assert!((0..=100).contains(&ore));
+///
+/// // Raw Q16.16 values between -1.0 and 1.0
+/// let raw = richness.get_raw(17, -4);
+/// assert!(raw.abs() <= FixedNoise::ONE);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FixedNoise {
+    seed: u32,
+    output_version: u32,
+    kind: FixedNoiseKind,
+    cell_size: u32,
+    range_min: i32,
+    range_max: i32,
+}
+
+impl FixedNoise {
+    /// 1.0 in Q16.16 fixed point.
+    pub const ONE: i32 = 1 << FRACTION_BITS;
+
+    /// Create gradient noise with the given seed.
+    #[must_use]
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            output_version: NOISE_OUTPUT_VERSION,
+            kind: FixedNoiseKind::Gradient,
+            cell_size: DEFAULT_CELL_SIZE,
+            range_min: 0,
+            range_max: Self::ONE,
+        }
+    }
+
+    /// Produce the output of an earlier [`NOISE_OUTPUT_VERSION`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` is not between 1 and [`NOISE_OUTPUT_VERSION`].
+    #[must_use]
+    pub fn with_output_version(mut self, version: u32) -> Self {
+        self.output_version = UnsupportedOutputVersion::expect(version);
+        self
+    }
+
+    /// Get the output version.
+    #[must_use]
+    pub fn output_version(&self) -> u32 {
+        self.output_version
+    }
+
+    /// Set the interpolation scheme.
+    #[must_use]
+    pub fn with_kind(mut self, kind: FixedNoiseKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Set the lattice spacing in input units. Larger cells create
+    /// smoother, larger features. Defaults to 128.
+    #[must_use]
+    pub fn with_cell_size(mut self, cell_size: u32) -> Self {
+        self.cell_size = cell_size.clamp(1, i32::MAX as u32);
+        self
+    }
+
+    /// Set the output range of [`Self::get_scaled`]. Defaults to
+    /// `0..=ONE`.
+    ///
+    /// `min` may be greater than `max` to invert the output.
+    #[must_use]
+    pub fn with_range(mut self, min: i32, max: i32) -> Self {
+        self.range_min = min;
+        self.range_max = max;
+        self
+    }
+
+    /// Get the seed.
+    #[must_use]
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Get raw 2D noise, between `-ONE` and `ONE`.
+    #[must_use]
+    pub fn get_raw(&self, x: i32, y: i32) -> i32 {
+        self.evaluate([x, y], self.cell_size)
+    }
+
+    /// Get raw 3D noise, between `-ONE` and `ONE`.
+    #[must_use]
+    pub fn get_raw_3d(&self, x: i32, y: i32, z: i32) -> i32 {
+        self.evaluate([x, y, z], self.cell_size)
+    }
+
+    /// Get normalized 2D noise, between 0 and `ONE`.
+    #[must_use]
+    pub fn get_normalized(&self, x: i32, y: i32) -> i32 {
+        normalize(self.get_raw(x, y))
+    }
+
+    /// Get normalized 3D noise, between 0 and `ONE`.
+    #[must_use]
+    pub fn get_normalized_3d(&self, x: i32, y: i32, z: i32) -> i32 {
+        normalize(self.get_raw_3d(x, y, z))
+    }
+
+    /// Get 2D noise mapped to the configured range.
+    #[must_use]
+    pub fn get_scaled(&self, x: i32, y: i32) -> i32 {
+        self.map_to_range(self.get_normalized(x, y))
+    }
+
+    /// Get 3D noise mapped to the configured range.
+    #[must_use]
+    pub fn get_scaled_3d(&self, x: i32, y: i32, z: i32) -> i32 {
+        self.map_to_range(self.get_normalized_3d(x, y, z))
+    }
+
+    /// Generate 2D fractal noise by combining multiple octaves, between
+    /// `-ONE` and `ONE`.
+    ///
+    /// Each octave halves the cell size and multiplies the amplitude by
+    /// `persistence`, a Q16.16 value (`ONE / 2` for the usual 0.5).
+    #[must_use]
+    pub fn get_fractal(&self, x: i32, y: i32, octaves: u32, persistence: i32) -> i32 {
+        self.fractal([x, y], octaves, persistence)
+    }
+
+    /// Generate 3D fractal noise by combining multiple octaves, between
+    /// `-ONE` and `ONE`.
+    #[must_use]
+    pub fn get_fractal_3d(&self, x: i32, y: i32, z: i32, octaves: u32, persistence: i32) -> i32 {
+        self.fractal([x, y, z], octaves, persistence)
+    }
+
+    /// Map a normalized value (0 to `ONE`) into the configured range,
+    /// rounding towards `min`.
+    #[must_use]
+    pub fn map_to_range(&self, normalized: i32) -> i32 {
+        let (min, max) = (i64::from(self.range_min), i64::from(self.range_max));
+        let value = min + ((max - min) * i64::from(normalized)).div_euclid(ONE);
+        value as i32
+    }
+
+    fn fractal<const DIM: usize>(&self, point: [i32; DIM], octaves: u32, persistence: i32) -> i32 {
+        let mut value = 0_i64;
+        let mut amplitude = ONE;
+        let mut max_value = 0_i64;
+        let mut cell_size = self.cell_size;
+
+        for _ in 0..octaves.max(1) {
+            value += mul(i64::from(self.evaluate(point, cell_size)), amplitude);
+            max_value += amplitude;
+            amplitude = mul(amplitude, i64::from(persistence));
+            cell_size = (cell_size / 2).max(1);
+        }
+
+        if max_value <= 0 {
+            return 0;
+        }
+        (value * ONE / max_value).clamp(-ONE, ONE) as i32
+    }
+
+    fn evaluate<const DIM: usize>(&self, point: [i32; DIM], cell_size: u32) -> i32 {
+        // When NOISE_OUTPUT_VERSION is bumped, keep the previous algorithm
+        // under its version here so saved seeds reproduce their worlds.
+        match self.output_version {
+            1 => self.evaluate_v1(point, cell_size),
+            version => unreachable!("{}", UnsupportedOutputVersion(version)),
+        }
+    }
+
+    fn evaluate_v1<const DIM: usize>(&self, point: [i32; DIM], cell_size: u32) -> i32 {
+        let size = i64::from(cell_size);
+        let mut cell = [0_i64; DIM];
+        let mut local = [0_i64; DIM];
+        let mut fade = [0_i64; DIM];
+        for axis in 0..DIM {
+            let p = i64::from(point[axis]);
+            cell[axis] = p.div_euclid(size);
+            local[axis] = (p.rem_euclid(size) << FRACTION_BITS) / size;
+            fade[axis] = quintic(local[axis]);
+        }
+
+        // Corner values, then interpolate one axis at a time as in the
+        // deterministic float backend
+        let mut corners = [0_i64; 8];
+        for (corner, value) in corners.iter_mut().enumerate().take(1 << DIM) {
+            let mut hash = u64::from(self.seed);
+            let mut offset = [0_i64; DIM];
+            for axis in 0..DIM {
+                let bit = ((corner >> axis) & 1) as i64;
+                hash = mix(hash ^ (cell[axis] + bit) as u64);
+                offset[axis] = local[axis] - bit * ONE;
+            }
+            *value = match self.kind {
+                FixedNoiseKind::Gradient => gradient_dot(hash, &offset),
+                // Top bits as a value in -ONE..ONE
+                FixedNoiseKind::Value => (hash >> (63 - FRACTION_BITS)) as i64 - ONE,
+            };
+        }
+        let mut count = 1 << DIM;
+        for t in fade {
+            count /= 2;
+            for i in 0..count {
+                let (a, b) = (corners[2 * i], corners[2 * i + 1]);
+                corners[i] = a + mul(b - a, t);
+            }
+        }
+
+        corners[0].clamp(-ONE, ONE) as i32
+    }
+}
+
+/// Map `-ONE..=ONE` to `0..=ONE`.
+#[inline]
+fn normalize(raw: i32) -> i32 {
+    (raw + FixedNoise::ONE) / 2
+}
+
+/// Dot product of the hashed corner gradient with a Q16.16 offset, using
+/// the same gradient sets as the deterministic float backend.
+fn gradient_dot<const DIM: usize>(hash: u64, offset: &[i64; DIM]) -> i64 {
+    let zeroed = if DIM > 2 {
+        ((hash >> 32) % DIM as u64) as usize
+    } else {
+        DIM
+    };
+    let mut sum = 0;
+    for (axis, &component) in offset.iter().enumerate() {
+        if axis == zeroed {
+            continue;
+        }
+        if (hash >> axis) & 1 == 0 {
+            sum += component;
+        } else {
+            sum -= component;
+        }
+    }
+    sum
+}
+
+/// Quintic fade curve `6t⁵ - 15t⁴ + 10t³` in Q16.16.
+#[inline]
+fn quintic(t: i64) -> i64 {
+    let inner = t * 6 - 15 * ONE;
+    let inner = mul(t, inner) + 10 * ONE;
+    let cube = mul(mul(t, t), t);
+    mul(cube, inner)
+}
+
+/// Product of two Q16.16 values.
+#[inline]
+fn mul(a: i64, b: i64) -> i64 {
+    (a * b) >> FRACTION_BITS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lattice_points_are_zero_and_values_stay_in_range() {
+        let noise = FixedNoise::new(7).with_cell_size(16);
+        for i in -8..8 {
+            assert_eq!(noise.get_raw(i * 16, 32), 0);
+            assert_eq!(noise.get_raw_3d(i * 16, -48, 16), 0);
+        }
+
+        let (mut min, mut max) = (i32::MAX, i32::MIN);
+        for y in -64..64 {
+            for x in -64..64 {
+                let raw = noise.get_raw(x * 3, y * 5);
+                min = min.min(raw);
+                max = max.max(raw);
+                let normalized = noise.get_normalized_3d(x, y, x - y);
+                assert!((0..=FixedNoise::ONE).contains(&normalized));
+            }
+        }
+        assert!(min < -FixedNoise::ONE / 2 && max > FixedNoise::ONE / 2);
+    }
+
+    #[test]
+    fn neighbors_change_smoothly() {
+        for kind in [FixedNoiseKind::Gradient, FixedNoiseKind::Value] {
+            let noise = FixedNoise::new(3).with_kind(kind).with_cell_size(64);
+            for x in -200..200 {
+                let step = (noise.get_raw(x + 1, 37) - noise.get_raw(x, 37)).abs();
+                // At most about 1/16 of the full range per unit
+                assert!(
+                    step < FixedNoise::ONE / 16,
+                    "{kind:?} jumped by {step} at {x}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fractal_and_range_mapping() {
+        let noise = FixedNoise::new(5).with_cell_size(64).with_range(10, 20);
+        assert_eq!(noise.map_to_range(0), 10);
+        assert_eq!(noise.map_to_range(FixedNoise::ONE), 20);
+        assert_eq!(noise.map_to_range(FixedNoise::ONE / 2), 15);
+
+        // A single octave is plain noise
+        for x in 0..50 {
+            assert_eq!(
+                noise.get_fractal(x * 7, 3, 1, FixedNoise::ONE / 2),
+                noise.get_raw(x * 7, 3)
+            );
+            let fractal = noise.get_fractal_3d(x * 7, 3, -x, 5, FixedNoise::ONE / 2);
+            assert!(fractal.abs() <= FixedNoise::ONE);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Float functions missing from `core`.
+//!
+//! With `std` these call the inherent `f64` methods. Without it they use
+//! `libm`, so the noise core builds for `no_std` targets.
+
+#[inline]
+pub(crate) fn floor(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    return x.floor();
+    #[cfg(not(feature = "std"))]
+    return libm::floor(x);
+}
+
+#[inline]
+pub(crate) fn ceil(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    return x.ceil();
+    #[cfg(not(feature = "std"))]
+    return libm::ceil(x);
+}
+
+#[inline]
+pub(crate) fn fract(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    return x.fract();
+    #[cfg(not(feature = "std"))]
+    return x - libm::trunc(x);
+}
+
+#[inline]
+pub(crate) fn powf(x: f64, exponent: f64) -> f64 {
+    #[cfg(feature = "std")]
+    return x.powf(exponent);
+    #[cfg(not(feature = "std"))]
+    return libm::pow(x, exponent);
+}
+
+#[inline]
+pub(crate) fn cbrt(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    return x.cbrt();
+    #[cfg(not(feature = "std"))]
+    return libm::cbrt(x);
+}
@@ -0,0 +1,478 @@
+//! Heightmap terrain meshes built from [`Noise`].
+//!
+//! [`HeightmapMeshBuilder`] samples a height function on a regular grid in
+//! the XZ plane and produces a Bevy [`Mesh`] with positions, normals and
+//! UVs. Chunks built from the same height function line up exactly, and
+//! optional skirts and neighbor LOD snapping hide seams between chunks of
+//! different detail.
+
+use crate::{Noise, SampleMode, SampleRegion};
+use bevy::asset::RenderAssetUsages;
+use bevy::math::{DVec2, UVec2, Vec2, Vec3};
+use bevy::mesh::{Indices, Mesh, PrimitiveTopology};
+use std::sync::Arc;
+
+/// Distance, in input coordinates, used for central differences when
+/// computing sampled normals.
+const GRADIENT_EPSILON: f64 = 0.01;
+
+/// How vertex normals are generated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeightmapNormals {
+    /// Derived from the gradient of the height function, estimated from
+    /// extra samples with central differences. Independent of
+    /// triangulation, so normals match across chunk borders and LOD levels.
+    /// On edges snapped to a coarser neighbor, the slope along the edge is
+    /// taken from the snapped heights.
+    #[default]
+    Sampled,
+    /// Averaged from the surrounding triangles of the mesh.
+    Computed,
+}
+
+/// An edge of a heightmap chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChunkEdge {
+    /// The edge at minimum x.
+    NegX,
+    /// The edge at maximum x.
+    PosX,
+    /// The edge at minimum z.
+    NegZ,
+    /// The edge at maximum z.
+    PosZ,
+}
+
+impl ChunkEdge {
+    const ALL: [Self; 4] = [Self::NegX, Self::PosX, Self::NegZ, Self::PosZ];
+
+    fn index(self) -> usize {
+        match self {
+            Self::NegX => 0,
+            Self::PosX => 1,
+            Self::NegZ => 2,
+            Self::PosZ => 3,
+        }
+    }
+}
+
+/// Builder for heightmap terrain meshes.
+///
+/// The region's x and y map to the mesh's x and z axes. Vertex positions
+/// are relative to the region origin, so place each chunk with a
+/// `Transform` at its origin.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::math::{DVec2, UVec2};
+/// use msg_noise::{HeightmapMeshBuilder, Noise, SampleMode, SampleRegion};
+///
+/// let terrain = Noise::new(12345);
+/// let mesh = HeightmapMeshBuilder::from_noise(&terrain, SampleMode::Normalized)
+///     .with_region(SampleRegion::new(DVec2::ZERO, DVec2::splat(64.0)))
+///     .with_resolution(UVec2::splat(32))
+///     .with_height_scale(20.0)
+///     .with_skirt(2.0)
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct HeightmapMeshBuilder {
+    sampler: Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>,
+    region: SampleRegion,
+    resolution: UVec2,
+    height_scale: f32,
+    normals: HeightmapNormals,
+    skirt_depth: Option<f32>,
+    lod: u32,
+    neighbor_lods: [u32; 4],
+    asset_usage: RenderAssetUsages,
+}
+
+impl HeightmapMeshBuilder {
+    /// Create a builder from a height function of world x and z.
+    #[must_use]
+    pub fn new(sampler: impl Fn(f64, f64) -> f64 + Send + Sync + 'static) -> Self {
+        Self {
+            sampler: Arc::new(sampler),
+            region: SampleRegion::new(DVec2::ZERO, DVec2::splat(64.0)),
+            resolution: UVec2::splat(64),
+            height_scale: 1.0,
+            normals: HeightmapNormals::default(),
+            skirt_depth: None,
+            lod: 0,
+            neighbor_lods: [0; 4],
+            asset_usage: RenderAssetUsages::default(),
+        }
+    }
+
+    /// Create a builder that samples `noise` with the given sampler.
+    #[must_use]
+    pub fn from_noise(noise: &Noise, mode: SampleMode) -> Self {
+        let noise = noise.clone();
+        Self::new(move |x, z| noise.sample(mode, x, z))
+    }
+
+    /// Set the region of the height function covered by the mesh.
+    #[must_use]
+    pub fn with_region(mut self, region: SampleRegion) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Set the number of grid cells per axis at LOD 0.
+    #[must_use]
+    pub fn with_resolution(mut self, resolution: UVec2) -> Self {
+        self.resolution = resolution.max(UVec2::ONE);
+        self
+    }
+
+    /// Set the multiplier applied to sampled heights.
+    #[must_use]
+    pub fn with_height_scale(mut self, height_scale: f32) -> Self {
+        self.height_scale = height_scale;
+        self
+    }
+
+    /// Set how normals are generated.
+    #[must_use]
+    pub fn with_normals(mut self, normals: HeightmapNormals) -> Self {
+        self.normals = normals;
+        self
+    }
+
+    /// Add skirts hanging `depth` units below every edge to hide cracks
+    /// between neighboring chunks.
+    #[must_use]
+    pub fn with_skirt(mut self, depth: f32) -> Self {
+        self.skirt_depth = Some(depth);
+        self
+    }
+
+    /// Set the level of detail. Each level halves the resolution.
+    #[must_use]
+    pub fn with_lod(mut self, lod: u32) -> Self {
+        self.lod = lod;
+        self
+    }
+
+    /// Set the level of detail of the neighboring chunk along `edge`.
+    ///
+    /// When the neighbor is coarser, vertices on the shared edge that the
+    /// neighbor does not have are snapped onto the neighbor's edge, so both
+    /// meshes meet without cracks.
+    #[must_use]
+    pub fn with_neighbor_lod(mut self, edge: ChunkEdge, lod: u32) -> Self {
+        self.neighbor_lods[edge.index()] = lod;
+        self
+    }
+
+    /// Set where the mesh asset is used. Defaults to both the main and
+    /// render worlds.
+    #[must_use]
+    pub fn with_asset_usage(mut self, asset_usage: RenderAssetUsages) -> Self {
+        self.asset_usage = asset_usage;
+        self
+    }
+
+    /// Number of grid cells per axis at the configured LOD.
+    #[must_use]
+    pub fn lod_resolution(&self) -> UVec2 {
+        lod_resolution(self.resolution, self.lod)
+    }
+
+    /// Build the mesh.
+    #[must_use]
+    pub fn build(&self) -> Mesh {
+        crate::timed(|| self.build_mesh())
+    }
+
+    fn build_mesh(&self) -> Mesh {
+        let cells = self.lod_resolution();
+        let columns = cells.x + 1;
+        let rows = cells.y + 1;
+        let cell_size = self.region.size / cells.as_dvec2();
+
+        let sampled = self.normals == HeightmapNormals::Sampled;
+        let mut heights = Vec::with_capacity((columns * rows) as usize);
+        let mut gradients = Vec::new();
+        for j in 0..rows {
+            for i in 0..columns {
+                let local = DVec2::new(f64::from(i), f64::from(j)) * cell_size;
+                let world = self.region.origin + local;
+                heights.push(self.height(world));
+                if sampled {
+                    gradients.push(self.gradient(world));
+                }
+            }
+        }
+        self.snap_to_neighbors(&mut heights, &mut gradients, cells, cell_size.as_vec2());
+
+        let mut positions = Vec::with_capacity(heights.len());
+        let mut uvs = Vec::with_capacity(heights.len());
+        for j in 0..rows {
+            for i in 0..columns {
+                let local = DVec2::new(f64::from(i), f64::from(j)) * cell_size;
+                let height = heights[(j * columns + i) as usize];
+                positions.push([local.x as f32, height, local.y as f32]);
+                uvs.push([i as f32 / cells.x as f32, j as f32 / cells.y as f32]);
+            }
+        }
+
+        let mut indices = Vec::with_capacity((cells.x * cells.y * 6) as usize);
+        for j in 0..cells.y {
+            for i in 0..cells.x {
+                let i00 = j * columns + i;
+                let i10 = i00 + 1;
+                let i01 = i00 + columns;
+                let i11 = i01 + 1;
+                indices.extend_from_slice(&[i00, i01, i10, i10, i01, i11]);
+            }
+        }
+
+        let mut normals = match self.normals {
+            HeightmapNormals::Sampled => gradients
+                .iter()
+                .map(|gradient| {
+                    Vec3::new(-gradient.x, 1.0, -gradient.y)
+                        .normalize_or(Vec3::Y)
+                        .to_array()
+                })
+                .collect(),
+            HeightmapNormals::Computed => computed_normals(&positions, &indices),
+        };
+
+        if let Some(depth) = self.skirt_depth {
+            add_skirts(
+                &mut positions,
+                &mut normals,
+                &mut uvs,
+                &mut indices,
+                UVec2::new(columns, rows),
+                depth,
+            );
+        }
+
+        Mesh::new(PrimitiveTopology::TriangleList, self.asset_usage)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+            .with_inserted_indices(Indices::U32(indices))
+    }
+
+    fn height(&self, world: DVec2) -> f32 {
+        (self.sampler)(world.x, world.y) as f32 * self.height_scale
+    }
+
+    /// Slope of the height along x and z, from central differences.
+    fn gradient(&self, world: DVec2) -> Vec2 {
+        let e = GRADIENT_EPSILON;
+        let dx = self.height(world + DVec2::new(e, 0.0)) - self.height(world - DVec2::new(e, 0.0));
+        let dz = self.height(world + DVec2::new(0.0, e)) - self.height(world - DVec2::new(0.0, e));
+        Vec2::new(dx, dz) / (2.0 * e as f32)
+    }
+
+    /// Snap edge heights onto coarser neighbors' edges.
+    ///
+    /// Gradients, if any, follow the snapped edge: the slope along it is
+    /// taken from the snapped heights and the slope across it is
+    /// interpolated like the heights.
+    fn snap_to_neighbors(
+        &self,
+        heights: &mut [f32],
+        gradients: &mut [Vec2],
+        cells: UVec2,
+        cell_size: Vec2,
+    ) {
+        let columns = cells.x + 1;
+        for edge in ChunkEdge::ALL {
+            let neighbor = self.neighbor_lods[edge.index()];
+            if neighbor <= self.lod {
+                continue;
+            }
+            let ratio = 1_u32 << (neighbor - self.lod).min(31);
+
+            // Edges at constant x run along z, and the other way around
+            let (count, along, index_of): (u32, usize, Box<dyn Fn(u32) -> usize>) = match edge {
+                ChunkEdge::NegX => (cells.y, 1, Box::new(move |k| (k * columns) as usize)),
+                ChunkEdge::PosX => (
+                    cells.y,
+                    1,
+                    Box::new(move |k| (k * columns + cells.x) as usize),
+                ),
+                ChunkEdge::NegZ => (cells.x, 0, Box::new(move |k| k as usize)),
+                ChunkEdge::PosZ => (
+                    cells.x,
+                    0,
+                    Box::new(move |k| (cells.y * columns + k) as usize),
+                ),
+            };
+            let across = 1 - along;
+
+            for k in 0..=count {
+                if k % ratio == 0 {
+                    continue;
+                }
+                let lower = k - k % ratio;
+                let upper = (lower + ratio).min(count);
+                let t = (k - lower) as f32 / (upper - lower) as f32;
+                let a = heights[index_of(lower)];
+                let b = heights[index_of(upper)];
+                heights[index_of(k)] = a + (b - a) * t;
+
+                if !gradients.is_empty() {
+                    let (ga, gb) = (gradients[index_of(lower)], gradients[index_of(upper)]);
+                    let gradient = &mut gradients[index_of(k)];
+                    gradient[along] = (b - a) / ((upper - lower) as f32 * cell_size[along]);
+                    gradient[across] = ga[across] + (gb[across] - ga[across]) * t;
+                }
+            }
+        }
+    }
+}
+
+/// Number of grid cells per axis at `lod`.
+fn lod_resolution(resolution: UVec2, lod: u32) -> UVec2 {
+    (resolution >> lod.min(31)).max(UVec2::ONE)
+}
+
+/// Area-weighted vertex normals from triangles.
+fn computed_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| i as usize);
+        let pa = Vec3::from_array(positions[a]);
+        let pb = Vec3::from_array(positions[b]);
+        let pc = Vec3::from_array(positions[c]);
+        let normal = (pb - pa).cross(pc - pa);
+        normals[a] += normal;
+        normals[b] += normal;
+        normals[c] += normal;
+    }
+    normals
+        .into_iter()
+        .map(|n| n.normalize_or(Vec3::Y).to_array())
+        .collect()
+}
+
+/// Append skirt vertices and triangles around the grid edges.
+fn add_skirts(
+    positions: &mut Vec<[f32; 3]>,
+    normals: &mut Vec<[f32; 3]>,
+    uvs: &mut Vec<[f32; 2]>,
+    indices: &mut Vec<u32>,
+    grid: UVec2,
+    depth: f32,
+) {
+    let (columns, rows) = (grid.x, grid.y);
+    let at = |i: u32, j: u32| j * columns + i;
+
+    // Each edge is walked so that its skirt faces outward
+    let edges: [Vec<u32>; 4] = [
+        (0..columns).map(|i| at(i, 0)).collect(),
+        (0..rows).map(|j| at(columns - 1, j)).collect(),
+        (0..columns).rev().map(|i| at(i, rows - 1)).collect(),
+        (0..rows).rev().map(|j| at(0, j)).collect(),
+    ];
+
+    for edge in edges {
+        let base = positions.len() as u32;
+        for &top in &edge {
+            let [x, y, z] = positions[top as usize];
+            positions.push([x, y - depth, z]);
+            normals.push(normals[top as usize]);
+            uvs.push(uvs[top as usize]);
+        }
+        for (k, pair) in edge.windows(2).enumerate() {
+            let bottom = base + k as u32;
+            indices.extend_from_slice(&[pair[0], pair[1], bottom, bottom, pair[1], bottom + 1]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::mesh::VertexAttributeValues;
+
+    fn positions(mesh: &Mesh) -> &[[f32; 3]] {
+        match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(values)) => values,
+            _ => panic!("missing positions"),
+        }
+    }
+
+    #[test]
+    fn flat_heightmap_has_upward_normals() {
+        let mesh = HeightmapMeshBuilder::new(|_, _| 0.5)
+            .with_resolution(UVec2::splat(4))
+            .with_normals(HeightmapNormals::Computed)
+            .build();
+
+        assert_eq!(mesh.count_vertices(), 25);
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("missing normals");
+        };
+        for normal in normals {
+            assert!((normal[1] - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn lod_halves_resolution() {
+        let builder = HeightmapMeshBuilder::new(|x, _| x).with_resolution(UVec2::splat(16));
+
+        assert_eq!(
+            builder.clone().with_lod(2).lod_resolution(),
+            UVec2::splat(4)
+        );
+        assert_eq!(builder.with_lod(2).build().count_vertices(), 25);
+    }
+
+    #[test]
+    fn coarser_neighbor_snaps_shared_edge() {
+        let sampler = |x: f64, z: f64| (x * 0.37).sin() + (z * 0.21).cos();
+        let region = SampleRegion::new(DVec2::ZERO, DVec2::splat(16.0));
+
+        let fine = HeightmapMeshBuilder::new(sampler)
+            .with_region(region)
+            .with_resolution(UVec2::splat(8))
+            .with_neighbor_lod(ChunkEdge::PosX, 1)
+            .build();
+        let fine = positions(&fine);
+
+        // Odd vertices on the +X edge lie halfway between their even neighbors
+        for j in (1..8).step_by(2) {
+            let index = |j: usize| j * 9 + 8;
+            let expected = (fine[index(j - 1)][1] + fine[index(j + 1)][1]) * 0.5;
+            assert!((fine[index(j)][1] - expected).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn snapped_edge_normals_follow_snapped_heights() {
+        let sampler = |x: f64, z: f64| (x * 0.37).sin() + (z * 0.21).cos() * 3.0;
+        let mesh = HeightmapMeshBuilder::new(sampler)
+            .with_region(SampleRegion::new(DVec2::ZERO, DVec2::splat(16.0)))
+            .with_resolution(UVec2::splat(8))
+            .with_neighbor_lod(ChunkEdge::PosX, 1)
+            .build();
+        let positions = positions(&mesh);
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("missing normals");
+        };
+
+        // Along the snapped +X edge, the z slope of odd vertices is the slope
+        // of the coarse segment they lie on
+        let index = |j: usize| j * 9 + 8;
+        for j in (1..8).step_by(2) {
+            let slope = (positions[index(j + 1)][1] - positions[index(j - 1)][1]) / 4.0;
+            let normal = normals[index(j)];
+            assert!((-normal[2] / normal[1] - slope).abs() < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,533 @@
+//! Isosurface extraction from 3D noise density fields.
+//!
+//! [`IsosurfaceBuilder`] samples a density function on a regular grid
+//! and extracts the surface where the density crosses a threshold, for
+//! caves, overhangs and floating islands. Chunks are seam-free: vertices on
+//! shared borders are computed from the same world-space samples by both
+//! neighbors.
+
+use crate::{Noise, SampleMode};
+use bevy::asset::RenderAssetUsages;
+use bevy::math::{DVec3, IVec3, UVec3, Vec3};
+use bevy::mesh::{Indices, Mesh, PrimitiveTopology};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Distance, in input coordinates, used for central differences when
+/// computing normals.
+const GRADIENT_EPSILON: f64 = 0.01;
+
+/// Corner offsets of a grid cell, indexed by bit pattern `x | y << 1 | z << 2`.
+const CORNERS: [IVec3; 8] = [
+    IVec3::new(0, 0, 0),
+    IVec3::new(1, 0, 0),
+    IVec3::new(0, 1, 0),
+    IVec3::new(1, 1, 0),
+    IVec3::new(0, 0, 1),
+    IVec3::new(1, 0, 1),
+    IVec3::new(0, 1, 1),
+    IVec3::new(1, 1, 1),
+];
+
+/// Cell edges as pairs of corner indices.
+const EDGES: [(usize, usize); 12] = [
+    (0, 1),
+    (2, 3),
+    (4, 5),
+    (6, 7),
+    (0, 2),
+    (1, 3),
+    (4, 6),
+    (5, 7),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+];
+
+/// Six tetrahedra sharing the cell diagonal from corner 0 to corner 7.
+///
+/// Every cell face is split along the same diagonal, so neighboring cells
+/// and chunks triangulate shared faces identically.
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 3, 2, 7],
+    [0, 2, 6, 7],
+    [0, 6, 4, 7],
+    [0, 4, 5, 7],
+    [0, 5, 1, 7],
+];
+
+/// Surface extraction algorithm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IsosurfaceMethod {
+    /// Naive surface nets: one vertex per surface cell, placed at the
+    /// average of its edge crossings. Produces smooth, well-shaped quads.
+    #[default]
+    SurfaceNets,
+    /// Marching cubes with each cell split into six tetrahedra. Vertices
+    /// lie exactly on the interpolated surface and there are no ambiguous
+    /// cases.
+    MarchingTetrahedra,
+}
+
+/// Extracted isosurface geometry.
+///
+/// Positions are relative to the chunk origin. Use [`Self::to_mesh`] to
+/// build a Bevy [`Mesh`], or use the buffers directly for colliders.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Isosurface {
+    /// Vertex positions.
+    pub positions: Vec<[f32; 3]>,
+    /// Vertex normals, pointing from solid towards empty space.
+    pub normals: Vec<[f32; 3]>,
+    /// Triangle list indices with counter-clockwise front faces.
+    pub indices: Vec<u32>,
+}
+
+impl Isosurface {
+    /// Whether the surface has no triangles.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Build a Bevy [`Mesh`] from the surface.
+    #[must_use]
+    pub fn to_mesh(&self, asset_usage: RenderAssetUsages) -> Mesh {
+        Mesh::new(PrimitiveTopology::TriangleList, asset_usage)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions.clone())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals.clone())
+            .with_inserted_indices(Indices::U32(self.indices.clone()))
+    }
+}
+
+/// Builder for extracting isosurfaces from a 3D density function.
+///
+/// Points where the density is at or above the threshold are solid.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::math::{DVec3, UVec3};
+/// use msg_noise::{IsosurfaceBuilder, IsosurfaceMethod, Noise, SampleMode};
+///
+/// let caves = Noise::new(12345).with_scale(0.05);
+/// let mesh = IsosurfaceBuilder::from_noise(&caves, SampleMode::Normalized)
+///     .with_chunk(DVec3::ZERO, DVec3::splat(32.0))
+///     .with_resolution(UVec3::splat(16))
+///     .with_threshold(0.5)
+///     .with_method(IsosurfaceMethod::SurfaceNets)
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct IsosurfaceBuilder {
+    sampler: Arc<dyn Fn(f64, f64, f64) -> f64 + Send + Sync>,
+    origin: DVec3,
+    size: DVec3,
+    resolution: UVec3,
+    threshold: f64,
+    method: IsosurfaceMethod,
+    asset_usage: RenderAssetUsages,
+}
+
+impl IsosurfaceBuilder {
+    /// Create a builder from a density function of world x, y and z.
+    #[must_use]
+    pub fn new(sampler: impl Fn(f64, f64, f64) -> f64 + Send + Sync + 'static) -> Self {
+        Self {
+            sampler: Arc::new(sampler),
+            origin: DVec3::ZERO,
+            size: DVec3::splat(32.0),
+            resolution: UVec3::splat(32),
+            threshold: 0.5,
+            method: IsosurfaceMethod::default(),
+            asset_usage: RenderAssetUsages::default(),
+        }
+    }
+
+    /// Create a builder that uses the 3D sampler selected by `mode` as the
+    /// density.
+    #[must_use]
+    pub fn from_noise(noise: &Noise, mode: SampleMode) -> Self {
+        let noise = noise.clone();
+        Self::new(move |x, y, z| noise.sample_3d(mode, x, y, z))
+    }
+
+    /// Set the chunk's minimum corner and size in world coordinates.
+    #[must_use]
+    pub fn with_chunk(mut self, origin: DVec3, size: DVec3) -> Self {
+        self.origin = origin;
+        self.size = size;
+        self
+    }
+
+    /// Set the number of grid cells per axis.
+    #[must_use]
+    pub fn with_resolution(mut self, resolution: UVec3) -> Self {
+        self.resolution = resolution.max(UVec3::ONE);
+        self
+    }
+
+    /// Set the density at which the surface is extracted.
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Set the extraction algorithm.
+    #[must_use]
+    pub fn with_method(mut self, method: IsosurfaceMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Set where the mesh asset is used. Defaults to both the main and
+    /// render worlds.
+    #[must_use]
+    pub fn with_asset_usage(mut self, asset_usage: RenderAssetUsages) -> Self {
+        self.asset_usage = asset_usage;
+        self
+    }
+
+    /// Extract the surface geometry.
+    #[must_use]
+    pub fn extract(&self) -> Isosurface {
+        // Sample one extra layer of points on every side so surface nets can
+        // see the cells of neighboring chunks.
+        let grid = DensityGrid::sample(self, IVec3::splat(-1), self.resolution.as_ivec3() + 2);
+        let mut surface = match self.method {
+            IsosurfaceMethod::SurfaceNets => surface_nets(self, &grid),
+            IsosurfaceMethod::MarchingTetrahedra => marching_tetrahedra(self, &grid),
+        };
+        surface.normals = surface
+            .positions
+            .iter()
+            .map(|p| self.normal(self.origin + Vec3::from_array(*p).as_dvec3()))
+            .collect();
+        surface
+    }
+
+    /// Extract the surface and build a Bevy [`Mesh`].
+    #[must_use]
+    pub fn build(&self) -> Mesh {
+        crate::timed(|| self.extract().to_mesh(self.asset_usage))
+    }
+
+    fn cell_size(&self) -> DVec3 {
+        self.size / self.resolution.as_dvec3()
+    }
+
+    /// Local position of grid point `point`.
+    fn local_position(&self, point: IVec3) -> DVec3 {
+        point.as_dvec3() * self.cell_size()
+    }
+
+    fn density(&self, world: DVec3) -> f64 {
+        (self.sampler)(world.x, world.y, world.z)
+    }
+
+    /// Surface normal at `world`, pointing towards decreasing density.
+    fn normal(&self, world: DVec3) -> [f32; 3] {
+        let e = GRADIENT_EPSILON;
+        let gradient = DVec3::new(
+            self.density(world + DVec3::X * e) - self.density(world - DVec3::X * e),
+            self.density(world + DVec3::Y * e) - self.density(world - DVec3::Y * e),
+            self.density(world + DVec3::Z * e) - self.density(world - DVec3::Z * e),
+        );
+        (-gradient).as_vec3().normalize_or(Vec3::Y).to_array()
+    }
+
+    /// Interpolated surface crossing between two grid points.
+    fn crossing(&self, grid: &DensityGrid, a: IVec3, b: IVec3) -> DVec3 {
+        let (da, db) = (grid.get(a), grid.get(b));
+        let t = if (db - da).abs() > f64::EPSILON {
+            ((self.threshold - da) / (db - da)).clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+        self.local_position(a).lerp(self.local_position(b), t)
+    }
+}
+
+/// Density samples on grid points `min..min + size`.
+struct DensityGrid {
+    min: IVec3,
+    size: IVec3,
+    values: Vec<f64>,
+}
+
+impl DensityGrid {
+    fn sample(builder: &IsosurfaceBuilder, min: IVec3, size: IVec3) -> Self {
+        let mut values = Vec::with_capacity((size.x * size.y * size.z) as usize);
+        for z in 0..size.z {
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    let point = min + IVec3::new(x, y, z);
+                    values.push(builder.density(builder.origin + builder.local_position(point)));
+                }
+            }
+        }
+        Self { min, size, values }
+    }
+
+    fn index(&self, point: IVec3) -> usize {
+        let p = point - self.min;
+        (p.x + self.size.x * (p.y + self.size.y * p.z)) as usize
+    }
+
+    fn get(&self, point: IVec3) -> f64 {
+        self.values[self.index(point)]
+    }
+}
+
+fn surface_nets(builder: &IsosurfaceBuilder, grid: &DensityGrid) -> Isosurface {
+    let threshold = builder.threshold;
+    let resolution = builder.resolution.as_ivec3();
+    let solid = |point: IVec3| grid.get(point) >= threshold;
+
+    // One vertex per cell that the surface passes through, including the
+    // border cells shared with neighboring chunks.
+    let mut surface = Isosurface::default();
+    let mut cell_vertices = HashMap::new();
+    for z in -1..resolution.z {
+        for y in -1..resolution.y {
+            for x in -1..resolution.x {
+                let cell = IVec3::new(x, y, z);
+                let mut sum = DVec3::ZERO;
+                let mut crossings = 0;
+                for (a, b) in EDGES {
+                    let (pa, pb) = (cell + CORNERS[a], cell + CORNERS[b]);
+                    if solid(pa) != solid(pb) {
+                        sum += builder.crossing(grid, pa, pb);
+                        crossings += 1;
+                    }
+                }
+                if crossings > 0 {
+                    cell_vertices.insert(cell, surface.positions.len() as u32);
+                    surface
+                        .positions
+                        .push((sum / f64::from(crossings)).as_vec3().to_array());
+                }
+            }
+        }
+    }
+
+    // One quad per grid edge owned by this chunk that crosses the surface.
+    // Each axis lists the edge direction and the two axes spanning the quad,
+    // ordered so their cross product is the edge direction.
+    let axes = [
+        (IVec3::X, IVec3::Y, IVec3::Z),
+        (IVec3::Y, IVec3::Z, IVec3::X),
+        (IVec3::Z, IVec3::X, IVec3::Y),
+    ];
+    for z in 0..resolution.z {
+        for y in 0..resolution.y {
+            for x in 0..resolution.x {
+                let point = IVec3::new(x, y, z);
+                for (direction, u, v) in axes {
+                    let start_solid = solid(point);
+                    if start_solid == solid(point + direction) {
+                        continue;
+                    }
+                    let quad = [point - u - v, point - v, point, point - u]
+                        .map(|cell| cell_vertices.get(&cell).copied());
+                    let [Some(a), Some(b), Some(c), Some(d)] = quad else {
+                        continue;
+                    };
+                    // Normals point from solid to empty along the edge
+                    if start_solid {
+                        surface.indices.extend_from_slice(&[a, b, c, a, c, d]);
+                    } else {
+                        surface.indices.extend_from_slice(&[a, c, b, a, d, c]);
+                    }
+                }
+            }
+        }
+    }
+
+    compact(surface)
+}
+
+fn marching_tetrahedra(builder: &IsosurfaceBuilder, grid: &DensityGrid) -> Isosurface {
+    let threshold = builder.threshold;
+    let resolution = builder.resolution.as_ivec3();
+
+    let mut surface = Isosurface::default();
+    let mut edge_vertices: HashMap<(usize, usize), u32> = HashMap::new();
+    let mut vertex = |a: IVec3, b: IVec3, surface: &mut Isosurface| {
+        let key = (grid.index(a), grid.index(b));
+        let key = (key.0.min(key.1), key.0.max(key.1));
+        *edge_vertices.entry(key).or_insert_with(|| {
+            surface
+                .positions
+                .push(builder.crossing(grid, a, b).as_vec3().to_array());
+            surface.positions.len() as u32 - 1
+        })
+    };
+
+    for z in 0..resolution.z {
+        for y in 0..resolution.y {
+            for x in 0..resolution.x {
+                let cell = IVec3::new(x, y, z);
+                for tetrahedron in TETRAHEDRA {
+                    let points = tetrahedron.map(|corner| cell + CORNERS[corner]);
+
+                    // Split corners into inside and outside without allocating
+                    let mut corners = [IVec3::ZERO; 4];
+                    let (mut inside, mut outside) = (0, 4);
+                    for point in points {
+                        if grid.get(point) >= threshold {
+                            corners[inside] = point;
+                            inside += 1;
+                        } else {
+                            outside -= 1;
+                            corners[outside] = point;
+                        }
+                    }
+                    let (inside, outside) = corners.split_at(inside);
+
+                    let (triangles, count): ([[(IVec3, IVec3); 3]; 2], usize) = match inside.len() {
+                        1 => {
+                            let triangle = [
+                                (inside[0], outside[0]),
+                                (inside[0], outside[1]),
+                                (inside[0], outside[2]),
+                            ];
+                            ([triangle; 2], 1)
+                        }
+                        3 => {
+                            let triangle = [
+                                (inside[0], outside[0]),
+                                (inside[1], outside[0]),
+                                (inside[2], outside[0]),
+                            ];
+                            ([triangle; 2], 1)
+                        }
+                        2 => (
+                            [
+                                [
+                                    (inside[0], outside[0]),
+                                    (inside[0], outside[1]),
+                                    (inside[1], outside[1]),
+                                ],
+                                [
+                                    (inside[0], outside[0]),
+                                    (inside[1], outside[1]),
+                                    (inside[1], outside[0]),
+                                ],
+                            ],
+                            2,
+                        ),
+                        _ => continue,
+                    };
+
+                    // Orient triangles to face from solid towards empty
+                    let centroid = |points: &[IVec3]| {
+                        points.iter().map(|p| p.as_dvec3()).sum::<DVec3>() / points.len() as f64
+                    };
+                    let outward = centroid(outside) - centroid(inside);
+
+                    for edges in &triangles[..count] {
+                        let [a, b, c] = edges.map(|(p, q)| vertex(p, q, &mut surface));
+                        let [pa, pb, pc] =
+                            [a, b, c].map(|i| Vec3::from_array(surface.positions[i as usize]));
+                        let normal = (pb - pa).cross(pc - pa).as_dvec3();
+                        if normal.dot(outward) >= 0.0 {
+                            surface.indices.extend_from_slice(&[a, b, c]);
+                        } else {
+                            surface.indices.extend_from_slice(&[a, c, b]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    compact(surface)
+}
+
+/// Remove vertices not referenced by any triangle.
+fn compact(surface: Isosurface) -> Isosurface {
+    let mut remap = vec![u32::MAX; surface.positions.len()];
+    let mut positions = Vec::new();
+    let indices = surface
+        .indices
+        .iter()
+        .map(|&index| {
+            let slot = &mut remap[index as usize];
+            if *slot == u32::MAX {
+                *slot = positions.len() as u32;
+                positions.push(surface.positions[index as usize]);
+            }
+            *slot
+        })
+        .collect();
+
+    Isosurface {
+        positions,
+        normals: Vec::new(),
+        indices,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sphere(radius: f64) -> IsosurfaceBuilder {
+        // Signed distance: positive inside the sphere, linear along radii
+        IsosurfaceBuilder::new(move |x, y, z| {
+            radius - DVec3::new(x, y, z).distance(DVec3::splat(8.0))
+        })
+        .with_chunk(DVec3::ZERO, DVec3::splat(16.0))
+        .with_resolution(UVec3::splat(16))
+        .with_threshold(0.0)
+    }
+
+    fn assert_faces_outward(surface: &Isosurface) {
+        for triangle in surface.indices.chunks_exact(3) {
+            let [a, b, c] =
+                [0, 1, 2].map(|i| Vec3::from_array(surface.positions[triangle[i] as usize]));
+            let center = (a + b + c) / 3.0 - Vec3::splat(8.0);
+            assert!((b - a).cross(c - a).dot(center) > 0.0);
+        }
+    }
+
+    #[test]
+    fn surface_nets_sphere_faces_outward() {
+        let surface = sphere(5.3).extract();
+
+        assert!(!surface.is_empty());
+        assert_faces_outward(&surface);
+        for (position, normal) in surface.positions.iter().zip(&surface.normals) {
+            let radial = (Vec3::from_array(*position) - Vec3::splat(8.0)).normalize();
+            assert!(radial.dot(Vec3::from_array(*normal)) > 0.9);
+        }
+    }
+
+    #[test]
+    fn marching_tetrahedra_vertices_lie_on_surface() {
+        let surface = sphere(5.3)
+            .with_method(IsosurfaceMethod::MarchingTetrahedra)
+            .extract();
+
+        assert!(!surface.is_empty());
+        assert_faces_outward(&surface);
+        for position in &surface.positions {
+            let distance = Vec3::from_array(*position).distance(Vec3::splat(8.0));
+            assert!((distance - 5.3).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn empty_field_produces_no_geometry() {
+        let surface = IsosurfaceBuilder::new(|_, _, _| 0.0)
+            .with_threshold(0.5)
+            .extract();
+
+        assert!(surface.is_empty());
+        assert!(surface.positions.is_empty());
+    }
+}
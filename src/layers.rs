@@ -0,0 +1,474 @@
+//! Per-entity stacks of blended noise layers.
+//!
+//! Planets, asteroids and procedural creatures each carry their own
+//! [`NoiseLayers`]: named layers seeded from the entity's key, combined
+//! with per-layer weights and [`LayerBlend`] modes into a single value.
+//! Layer settings are reflected, so they can be edited in inspectors and
+//! saved with scenes; the generators themselves are rebuilt from the
+//! settings.
+
+use crate::{
+    DEFAULT_NOISE_SCALE, NOISE_OUTPUT_VERSION, Noise, NoiseSampler, NoiseSource, SampleMode,
+    hash_combine,
+};
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+use std::sync::OnceLock;
+
+/// How a layer is combined with the layers below it.
+///
+/// The layer's value is combined with the running value, and the result
+/// is blended in by the layer's weight, like layer opacity in an image
+/// editor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+pub enum LayerBlend {
+    /// Add the layer's value.
+    #[default]
+    Add,
+    /// Subtract the layer's value.
+    Subtract,
+    /// Multiply by the layer's value, e.g. to mask lower layers.
+    Multiply,
+    /// Keep the smaller value.
+    Min,
+    /// Keep the larger value.
+    Max,
+    /// Replace the running value.
+    Replace,
+}
+
+impl LayerBlend {
+    /// Blend `value` onto `below` with the given weight.
+    #[must_use]
+    pub fn apply(self, below: f64, value: f64, weight: f64) -> f64 {
+        let combined = match self {
+            Self::Add => below + value,
+            Self::Subtract => below - value,
+            Self::Multiply => below * value,
+            Self::Min => below.min(value),
+            Self::Max => below.max(value),
+            Self::Replace => value,
+        };
+        below + (combined - below) * weight
+    }
+}
+
+/// Settings of one layer in a [`NoiseLayers`] stack.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+pub struct NoiseLayer {
+    name: String,
+    scale: f64,
+    range: (f64, f64),
+    mode: SampleMode,
+    weight: f64,
+    blend: LayerBlend,
+}
+
+impl NoiseLayer {
+    /// Create a layer with the default scale, normalized sampling, a
+    /// weight of 1.0 and additive blending.
+    ///
+    /// The name also selects the layer's seed, so renaming a layer changes
+    /// its noise.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            scale: DEFAULT_NOISE_SCALE,
+            range: (0.0, 1.0),
+            mode: SampleMode::Normalized,
+            weight: 1.0,
+            blend: LayerBlend::Add,
+        }
+    }
+
+    /// Set the input scale (frequency).
+    #[must_use]
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set the output range used by the scaled sample modes.
+    #[must_use]
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.range = (min, max);
+        self
+    }
+
+    /// Set the sampler used for this layer.
+    #[must_use]
+    pub fn with_mode(mut self, mode: SampleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the blend weight. 0.0 disables the layer.
+    #[must_use]
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Set the blend mode.
+    #[must_use]
+    pub fn with_blend(mut self, blend: LayerBlend) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Get the layer name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the sampler used for this layer.
+    #[must_use]
+    pub fn mode(&self) -> SampleMode {
+        self.mode
+    }
+
+    /// Get the blend weight.
+    #[must_use]
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    /// Get the blend mode.
+    #[must_use]
+    pub fn blend(&self) -> LayerBlend {
+        self.blend
+    }
+
+    /// Build this layer's noise from the stack's source.
+    fn build(&self, source: &NoiseSource) -> Noise {
+        source
+            .create(name_key(&self.name))
+            .with_scale(self.scale)
+            .with_range(self.range.0, self.range.1)
+    }
+
+    /// Whether both layers build the same noise. Mode, weight and blend
+    /// are read when sampling and do not matter.
+    fn same_noise(&self, other: &Self) -> bool {
+        self.name == other.name && self.scale == other.scale && self.range == other.range
+    }
+}
+
+/// Layer generators and the settings they were built from.
+#[derive(Clone, Debug)]
+struct Generators {
+    seed: u32,
+    output_version: u32,
+    layers: Vec<NoiseLayer>,
+    noises: Vec<Noise>,
+}
+
+impl Generators {
+    /// Build the generators of `stack`. Falls back to the current
+    /// [`NOISE_OUTPUT_VERSION`] if the stack's version is unsupported,
+    /// e.g. in a scene saved by a newer release.
+    fn build(stack: &NoiseLayers) -> Self {
+        let source = NoiseSource::new(stack.seed)
+            .try_with_output_version(stack.output_version)
+            .unwrap_or_else(|error| {
+                log::warn!("{error}; building noise layers with version {NOISE_OUTPUT_VERSION}");
+                NoiseSource::new(stack.seed)
+            });
+        Self {
+            seed: stack.seed,
+            output_version: stack.output_version,
+            layers: stack.layers.clone(),
+            noises: stack.layers.iter().map(|l| l.build(&source)).collect(),
+        }
+    }
+
+    /// Whether these generators match the current settings of `stack`.
+    fn matches(&self, stack: &NoiseLayers) -> bool {
+        self.seed == stack.seed
+            && self.output_version == stack.output_version
+            && self.layers.len() == stack.layers.len()
+            && self
+                .layers
+                .iter()
+                .zip(&stack.layers)
+                .all(|(built, layer)| built.same_noise(layer))
+    }
+}
+
+/// A stack of named noise layers owned by an entity.
+///
+/// Layers are seeded from a [`NoiseSource`] and the entity's own key, and
+/// evaluated bottom to top starting from 0.0. Generators are built on
+/// first use, so stacks loaded from a scene or created through reflection
+/// work right away. Editing the reflected settings, e.g. in an inspector,
+/// marks the component changed and `NoisePlugin` rebuilds the generators.
+/// When editing reflected settings outside of an app, call
+/// [`Self::rebuild`]; until then, each sample builds temporary generators.
+/// A stack with an unsupported output version, e.g. from a scene saved by
+/// a newer release, logs a warning and uses the current version.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{LayerBlend, NoiseLayer, NoiseLayers, NoiseSource, SampleMode};
+///
+/// let source = NoiseSource::new(12345);
+/// let asteroid_id = 17;
+///
+/// let surface = NoiseLayers::new(&source, asteroid_id)
+///     .with_layer(NoiseLayer::new("shape").with_scale(0.5))
+///     .with_layer(
+///         NoiseLayer::new("craters")
+///             .with_scale(2.0)
+///             .with_mode(SampleMode::Absolute)
+///             .with_weight(0.3)
+///             .with_blend(LayerBlend::Subtract),
+///     );
+///
+/// let radius = 10.0 + surface.sample_3d(0.2, 0.9, 0.4);
+/// let shape = surface.sample_layer("shape", 0.2, 0.9);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "bevy", derive(Component, Reflect))]
+#[cfg_attr(feature = "bevy", reflect(Component, Default))]
+pub struct NoiseLayers {
+    seed: u32,
+    output_version: u32,
+    layers: Vec<NoiseLayer>,
+    #[cfg_attr(feature = "bevy", reflect(ignore))]
+    generators: OnceLock<Generators>,
+}
+
+impl Default for NoiseLayers {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            output_version: NOISE_OUTPUT_VERSION,
+            layers: Vec::new(),
+            generators: OnceLock::new(),
+        }
+    }
+}
+
+impl NoiseLayers {
+    /// Create an empty stack seeded from `source` and `key`.
+    ///
+    /// Uses the same key derivation as [`NoiseSource::create`], and keeps
+    /// the source's output version.
+    #[must_use]
+    pub fn new(source: &NoiseSource, key: u32) -> Self {
+        Self {
+            seed: hash_combine(source.seed(), key),
+            output_version: source.output_version(),
+            layers: Vec::new(),
+            generators: OnceLock::new(),
+        }
+    }
+
+    /// Add a layer on top of the stack. Adding a layer with an existing
+    /// name replaces it in place.
+    #[must_use]
+    pub fn with_layer(mut self, layer: NoiseLayer) -> Self {
+        self.insert_layer(layer);
+        self
+    }
+
+    /// Add a layer on top of the stack, replacing a layer with the same
+    /// name in place.
+    pub fn insert_layer(&mut self, layer: NoiseLayer) {
+        match self.layers.iter().position(|l| l.name == layer.name) {
+            Some(index) => self.layers[index] = layer,
+            None => self.layers.push(layer),
+        }
+        self.rebuild();
+    }
+
+    /// Remove a layer by name.
+    pub fn remove_layer(&mut self, name: &str) -> Option<NoiseLayer> {
+        let index = self.layers.iter().position(|l| l.name == name)?;
+        let layer = self.layers.remove(index);
+        self.rebuild();
+        Some(layer)
+    }
+
+    /// Get the layers, bottom first.
+    #[must_use]
+    pub fn layers(&self) -> &[NoiseLayer] {
+        &self.layers
+    }
+
+    /// Get the derived seed of this stack.
+    #[must_use]
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Get the noise of a layer.
+    ///
+    /// Returns `None` for unknown layers, and after reflected edits that
+    /// have not been rebuilt yet.
+    #[must_use]
+    pub fn noise(&self, name: &str) -> Option<&Noise> {
+        let index = self.layers.iter().position(|l| l.name == name)?;
+        let generators = self.generators();
+        generators.matches(self).then(|| &generators.noises[index])
+    }
+
+    /// Rebuild the layer generators from the current settings.
+    pub fn rebuild(&mut self) {
+        self.generators = OnceLock::from(Generators::build(self));
+    }
+
+    /// Generators, built on first use.
+    fn generators(&self) -> &Generators {
+        self.generators.get_or_init(|| Generators::build(self))
+    }
+
+    /// Run `f` with generators matching the current settings, building
+    /// temporary ones if the settings were edited without a rebuild.
+    fn with_noises<T>(&self, f: impl FnOnce(&[Noise]) -> T) -> T {
+        let generators = self.generators();
+        if generators.matches(self) {
+            f(&generators.noises)
+        } else {
+            f(&Generators::build(self).noises)
+        }
+    }
+
+    /// Sample the blended 2D value of all layers.
+    #[must_use]
+    pub fn sample(&self, x: f64, y: f64) -> f64 {
+        self.blend(|noise, mode| noise.sample(mode, x, y))
+    }
+
+    /// Sample the blended 3D value of all layers.
+    #[must_use]
+    pub fn sample_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.blend(|noise, mode| noise.sample_3d(mode, x, y, z))
+    }
+
+    /// Sample a single layer, ignoring its weight and blend mode.
+    #[must_use]
+    pub fn sample_layer(&self, name: &str, x: f64, y: f64) -> Option<f64> {
+        let index = self.layers.iter().position(|l| l.name == name)?;
+        let mode = self.layers[index].mode;
+        Some(self.with_noises(|noises| noises[index].sample(mode, x, y)))
+    }
+
+    fn blend(&self, sample: impl Fn(&Noise, SampleMode) -> f64) -> f64 {
+        self.with_noises(|noises| {
+            self.layers
+                .iter()
+                .zip(noises)
+                .fold(0.0, |value, (layer, noise)| {
+                    layer
+                        .blend
+                        .apply(value, sample(noise, layer.mode), layer.weight)
+                })
+        })
+    }
+}
+
+impl NoiseSampler for NoiseLayers {
+    /// Sample the blended 2D value. Values depend on the layer modes and
+    /// weights rather than being raw noise.
+    #[inline]
+    fn sample_raw(&self, x: f64, y: f64) -> f64 {
+        self.sample(x, y)
+    }
+
+    #[inline]
+    fn sample_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.sample_3d(x, y, z)
+    }
+}
+
+/// Rebuild the generators of stacks whose settings changed.
+#[cfg(feature = "bevy")]
+pub(crate) fn rebuild_noise_layers(mut query: Query<&mut NoiseLayers, Changed<NoiseLayers>>) {
+    for mut layers in &mut query {
+        layers.bypass_change_detection().rebuild();
+    }
+}
+
+/// Stable seed key for a layer name (FNV-1a).
+fn name_key(name: &str) -> u32 {
+    name.bytes().fold(0x811C_9DC5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_modes_respect_weight() {
+        assert_eq!(LayerBlend::Add.apply(1.0, 0.5, 1.0), 1.5);
+        assert_eq!(LayerBlend::Subtract.apply(1.0, 0.5, 0.5), 0.75);
+        assert_eq!(LayerBlend::Multiply.apply(0.8, 0.5, 1.0), 0.4);
+        assert_eq!(LayerBlend::Min.apply(0.8, 0.5, 1.0), 0.5);
+        assert_eq!(LayerBlend::Max.apply(0.8, 0.5, 0.0), 0.8);
+        assert_eq!(LayerBlend::Replace.apply(0.8, 0.2, 0.5), 0.5);
+    }
+
+    #[test]
+    fn layers_are_seeded_from_key_and_name() {
+        let source = NoiseSource::new(99);
+        let stack = |key| {
+            NoiseLayers::new(&source, key)
+                .with_layer(NoiseLayer::new("a").with_scale(0.1))
+                .with_layer(NoiseLayer::new("b").with_scale(0.1))
+        };
+        let (first, again, other) = (stack(1), stack(1), stack(2));
+
+        assert_eq!(first.sample(3.5, 4.5), again.sample(3.5, 4.5));
+        assert_ne!(first.sample(3.5, 4.5), other.sample(3.5, 4.5));
+        assert_ne!(
+            first.sample_layer("a", 3.5, 4.5),
+            first.sample_layer("b", 3.5, 4.5)
+        );
+        let sum =
+            first.sample_layer("a", 3.5, 4.5).unwrap() + first.sample_layer("b", 3.5, 4.5).unwrap();
+        assert!((first.sample(3.5, 4.5) - sum).abs() < 1e-12);
+    }
+
+    #[test]
+    fn generators_are_built_lazily_and_follow_edits() {
+        let source = NoiseSource::new(5);
+        let layers = NoiseLayers::new(&source, 3)
+            .with_layer(NoiseLayer::new("base").with_scale(0.2))
+            .with_layer(NoiseLayer::new("mask").with_blend(LayerBlend::Multiply));
+
+        // As after creation through reflection
+        let mut lazy = layers.clone();
+        lazy.generators = OnceLock::new();
+        assert_eq!(
+            lazy.sample_3d(1.0, 2.0, 3.0),
+            layers.sample_3d(1.0, 2.0, 3.0)
+        );
+        assert!(lazy.generators.get().is_some());
+        assert!(lazy.noise("base").is_some());
+
+        // In-place edit of a generator setting without a rebuild
+        lazy.layers[0].scale = 0.7;
+        let expected = NoiseLayers::new(&source, 3)
+            .with_layer(NoiseLayer::new("base").with_scale(0.7))
+            .with_layer(NoiseLayer::new("mask").with_blend(LayerBlend::Multiply));
+        assert_eq!(lazy.sample(1.0, 2.0), expected.sample(1.0, 2.0));
+        assert!(lazy.noise("base").is_none());
+        lazy.rebuild();
+        assert_eq!(
+            lazy.noise("base").unwrap().get_raw(1.0, 2.0),
+            expected.noise("base").unwrap().get_raw(1.0, 2.0)
+        );
+        assert_eq!(
+            NoiseSampler::sample_raw_3d(&lazy, 1.0, 2.0, 3.0),
+            expected.sample_3d(1.0, 2.0, 3.0)
+        );
+    }
+}
@@ -1,8 +1,10 @@
 //! Seeded noise generation for Bevy games.
 //!
 //! This crate provides deterministic, reproducible noise generation
-//! through a centralized [`NoiseSource`] resource that derives its seed
-//! from [`msg_rng::GlobalRng`].
+//! through a centralized [`NoiseSource`] that derives noise generators
+//! from a single seed. With the `bevy` feature (enabled by default) the
+//! source is a resource inserted by `NoisePlugin`, seeded explicitly or
+//! from `msg_rng::GlobalRng`.
 //!
 //! # Features
 //!
@@ -10,10 +12,24 @@
 //! - **Global source**: Single seed for all noise generators
 //! - **Factory pattern**: Create derived noise generators with unique keys
 //! - **Configurable**: Scale, range, offset, and fractal parameters
+//! - **Standalone core**: Without default features, only `glam` and
+//!   `noise` are required, for tools and servers that don't use Bevy
 //!
 //! # Quick Start
 //!
 //! ```rust
+//! use msg_noise::prelude::*;
+//!
+//! let noise_source = NoiseSource::new(12345);
+//! let terrain = noise_source.create(0x5445_5252); // "TERR"
+//! let value = terrain.get_normalized(10.0, 20.0);
+//! ```
+//!
+//! In a Bevy app, add `NoisePlugin` and read the source as a resource:
+//!
+//! ```rust
+//! # #[cfg(feature = "bevy")]
+//! # mod example {
 //! use bevy::prelude::*;
 //! use msg_rng::prelude::*;
 //! use msg_noise::prelude::*;
@@ -30,15 +46,163 @@
 //!     let terrain = noise_source.create(0x5445_5252); // "TERR"
 //!     let value = terrain.get_normalized(10.0, 20.0);
 //! }
+//! # }
 //! ```
+//!
+//! # `no_std`
+//!
+//! Without the default `std` feature the crate is `no_std` and needs only
+//! `alloc`. `Noise`, `NoiseSource`, calibration and remapping remain
+//! available, with float math provided by `libm`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+use alloc::sync::Arc;
+#[cfg(feature = "bevy")]
 use bevy::prelude::*;
+use core::fmt;
+use glam::{DAffine3, DQuat, DVec3};
+#[cfg(feature = "bevy")]
 use msg_rng::GlobalRng;
 use noise::{NoiseFn, Perlin, ScalePoint};
-use std::fmt;
+
+#[cfg(feature = "std")]
+mod animated;
+#[cfg(feature = "std")]
+mod biome;
+mod calibration;
+#[cfg(feature = "std")]
+mod contour;
+#[cfg(feature = "std")]
+mod curl;
+mod custom;
+mod deterministic;
+#[cfg(feature = "bevy")]
+mod diagnostics;
+mod fixed;
+mod float;
+#[cfg(feature = "mesh")]
+mod heightmap;
+#[cfg(feature = "mesh")]
+mod isosurface;
+#[cfg(feature = "std")]
+mod layers;
+#[cfg(feature = "std")]
+mod placement;
+#[cfg(feature = "preview")]
+mod preview;
+mod remap;
+mod sample;
+mod sampler;
+#[cfg(feature = "std")]
+mod scatter;
+#[cfg(feature = "std")]
+mod signal;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "image")]
+mod texture;
+#[cfg(feature = "std")]
+mod tile;
+
+#[cfg(feature = "std")]
+pub use animated::TimeNoise;
+#[cfg(feature = "image")]
+pub use animated::TimeNoiseImage;
+#[cfg(feature = "std")]
+pub use biome::{Biome, BiomeGrid, BiomeMap, BiomeSample};
+pub use calibration::{CalibrationSampler, CalibrationSettings, NoiseCalibration};
+#[cfg(feature = "std")]
+pub use contour::{Contour, ContourBuilder, ContourLevel};
+#[cfg(feature = "std")]
+pub use curl::{CurlNoise2D, CurlNoise3D, FlowField};
+pub use custom::CustomNoise;
+pub use deterministic::NoiseBackend;
+#[cfg(feature = "bevy")]
+pub use diagnostics::{NoiseDiagnosticsPlugin, NoiseDiagnosticsRecorder};
+pub use fixed::{FixedNoise, FixedNoiseKind};
+#[cfg(feature = "mesh")]
+pub use heightmap::{ChunkEdge, HeightmapMeshBuilder, HeightmapNormals};
+#[cfg(feature = "mesh")]
+pub use isosurface::{Isosurface, IsosurfaceBuilder, IsosurfaceMethod};
+#[cfg(feature = "std")]
+pub use layers::{LayerBlend, NoiseLayer, NoiseLayers};
+#[cfg(feature = "std")]
+pub use placement::{Placement, PlacementMask, PlacementRule, PlacementRules};
+#[cfg(feature = "preview")]
+pub use preview::{NoisePreview, NoisePreviewPlugin, NoiseRecipe, PreviewFrame};
+pub use remap::RemapCurve;
+pub use sample::{SampleMode, SampleRegion};
+pub use sampler::NoiseSampler;
+#[cfg(feature = "std")]
+pub use scatter::{Scatter, ScatterMethod};
+#[cfg(feature = "std")]
+pub use signal::NoiseSignal;
+#[cfg(feature = "std")]
+pub use stats::{NoiseStats, StatsRegion, StatsSettings};
+#[cfg(feature = "image")]
+pub use texture::{ColorRamp, NoiseImage, NoiseImageFormat};
+
+/// Math types used by the API, re-exported from `glam`.
+///
+/// These are the same types as `bevy::math`, so values can be passed
+/// between the two without conversion.
+pub use glam as math;
+
+/// Version of the values produced by the samplers.
+///
+/// Bumped whenever an algorithm change alters the output for the same
+/// seed and settings. Older versions remain selectable with
+/// [`NoiseSource::with_output_version`], so worlds generated from saved
+/// seeds stay the same after upgrading.
+pub const NOISE_OUTPUT_VERSION: u32 = 1;
+
+/// Error for an output version this crate version cannot produce, e.g. a
+/// version saved by a newer release.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnsupportedOutputVersion(pub u32);
+
+impl UnsupportedOutputVersion {
+    /// Check that `version` is between 1 and [`NOISE_OUTPUT_VERSION`].
+    fn check(version: u32) -> Result<u32, Self> {
+        if (1..=NOISE_OUTPUT_VERSION).contains(&version) {
+            Ok(version)
+        } else {
+            Err(Self(version))
+        }
+    }
+
+    /// Panic with the error unless `version` is supported.
+    #[track_caller]
+    fn expect(version: u32) -> u32 {
+        match Self::check(version) {
+            Ok(version) => version,
+            Err(error) => panic!("{error}"),
+        }
+    }
+}
+
+impl fmt::Display for UnsupportedOutputVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsupported noise output version {}, expected 1 to {NOISE_OUTPUT_VERSION}",
+            self.0
+        )
+    }
+}
+
+impl core::error::Error for UnsupportedOutputVersion {}
 
 const DEFAULT_NOISE_SCALE: f64 = 0.008;
 
+/// Noise-space y coordinate of the line sampled by the 1D samplers.
+///
+/// Kept off the integer lattice, where Perlin noise is always zero.
+const LINE_Y: f64 = 0.3;
+
 /// Plugin for adding noise generation to a Bevy app.
 ///
 /// # Examples
@@ -57,10 +221,12 @@ const DEFAULT_NOISE_SCALE: f64 = 0.008;
 /// App::new()
 ///     .add_plugins(NoisePlugin::seeded(12345));
 /// ```
+#[cfg(feature = "bevy")]
 pub struct NoisePlugin {
     seed: Option<u32>,
 }
 
+#[cfg(feature = "bevy")]
 impl NoisePlugin {
     /// Create a noise plugin with an explicit seed.
     #[must_use]
@@ -77,9 +243,37 @@ impl NoisePlugin {
     }
 }
 
+#[cfg(feature = "bevy")]
 impl Plugin for NoisePlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<NoiseSource>();
+        app.register_type::<NoiseSource>()
+            .register_type::<NoiseLayers>()
+            .add_systems(
+                PreUpdate,
+                (
+                    layers::rebuild_noise_layers,
+                    signal::remove_noise_signal_offsets,
+                ),
+            )
+            .add_systems(
+                Update,
+                (signal::update_noise_signals, animated::advance_time_noise)
+                    .run_if(resource_exists::<Time>),
+            )
+            .add_systems(
+                PostUpdate,
+                signal::apply_noise_signal_offsets
+                    .before(bevy::transform::TransformSystems::Propagate),
+            )
+            .add_observer(signal::remove_offset_of_removed_signal);
+
+        #[cfg(feature = "image")]
+        app.add_systems(
+            Update,
+            animated::update_time_noise_images
+                .after(animated::advance_time_noise)
+                .run_if(resource_exists::<Assets<Image>>),
+        );
 
         match self.seed {
             Some(seed) => {
@@ -104,31 +298,80 @@ impl Plugin for NoisePlugin {
 /// This is the primary source for creating noise generators.
 /// All noise generators are derived from this resource's seed.
 ///
+/// With the `bevy` feature, `NoisePlugin` inserts it as a resource.
+///
 /// # Examples
 ///
 /// ```rust
-/// use bevy::prelude::*;
 /// use msg_noise::NoiseSource;
 ///
-/// fn my_system(noise: Res<NoiseSource>) {
-///     // Create noise with a unique key
-///     let terrain = noise.create(0x5445_5252);
+/// let noise = NoiseSource::new(12345);
 ///
-///     // Get noise value at coordinates
-///     let height = terrain.get_normalized(10.0, 20.0);
-/// }
+/// // Create noise with a unique key
+/// let terrain = noise.create(0x5445_5252);
+///
+/// // Get noise value at coordinates
+/// let height = terrain.get_normalized(10.0, 20.0);
 /// ```
-#[derive(Resource, Reflect)]
-#[reflect(Resource)]
+#[cfg_attr(feature = "bevy", derive(Resource, Reflect))]
+#[cfg_attr(feature = "bevy", reflect(Resource))]
 pub struct NoiseSource {
     seed: u32,
+    output_version: u32,
 }
 
 impl NoiseSource {
     /// Create a new noise source with the given seed.
     #[must_use]
     pub fn new(seed: u32) -> Self {
-        Self { seed }
+        Self {
+            seed,
+            output_version: NOISE_OUTPUT_VERSION,
+        }
+    }
+
+    /// Produce the output of an earlier [`NOISE_OUTPUT_VERSION`].
+    ///
+    /// Store the version alongside saved seeds and restore it on load, so
+    /// worlds are regenerated identically after upgrading. Use
+    /// [`Self::try_with_output_version`] for versions read from saves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` is not between 1 and [`NOISE_OUTPUT_VERSION`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use msg_noise::{NOISE_OUTPUT_VERSION, NoiseSource};
+    ///
+    /// // When saving
+    /// let saved = (12345, NOISE_OUTPUT_VERSION);
+    ///
+    /// // When loading, possibly with a newer crate version
+    /// let source = NoiseSource::new(saved.0).with_output_version(saved.1);
+    /// let terrain = source.create(0x5445_5252);
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn with_output_version(mut self, version: u32) -> Self {
+        self.output_version = UnsupportedOutputVersion::expect(version);
+        self
+    }
+
+    /// Produce the output of an earlier [`NOISE_OUTPUT_VERSION`], or report
+    /// a version this crate version cannot produce.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnsupportedOutputVersion`] if `version` is not between 1
+    /// and [`NOISE_OUTPUT_VERSION`], e.g. for a save from a newer release.
+    pub fn try_with_output_version(
+        mut self,
+        version: u32,
+    ) -> Result<Self, UnsupportedOutputVersion> {
+        self.output_version = UnsupportedOutputVersion::check(version)?;
+        Ok(self)
     }
 
     /// Get the current seed.
@@ -137,6 +380,12 @@ impl NoiseSource {
         self.seed
     }
 
+    /// Get the output version of created noise generators.
+    #[must_use]
+    pub fn output_version(&self) -> u32 {
+        self.output_version
+    }
+
     /// Reseed the noise source.
     ///
     /// Use this when transitioning to a new level.
@@ -144,6 +393,16 @@ impl NoiseSource {
         self.seed = seed;
     }
 
+    /// Update the output version, e.g. when loading a saved world.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` is not between 1 and [`NOISE_OUTPUT_VERSION`].
+    #[track_caller]
+    pub fn set_output_version(&mut self, version: u32) {
+        self.output_version = UnsupportedOutputVersion::expect(version);
+    }
+
     /// Create a noise generator with a derived seed.
     ///
     /// The key is combined with the base seed to create a unique
@@ -151,7 +410,7 @@ impl NoiseSource {
     #[must_use]
     pub fn create(&self, key: u32) -> Noise {
         let derived = hash_combine(self.seed, key);
-        Noise::new(derived)
+        Noise::new(derived).with_output_version(self.output_version)
     }
 
     /// Create a noise generator using an additional salt value.
@@ -161,7 +420,26 @@ impl NoiseSource {
     pub fn create_salted(&self, key: u32, salt: u32) -> Noise {
         let combined = hash_combine(key, salt);
         let derived = hash_combine(self.seed, combined);
-        Noise::new(derived)
+        Noise::new(derived).with_output_version(self.output_version)
+    }
+
+    /// Create a point scatterer with a derived seed.
+    ///
+    /// Uses the same key derivation as [`Self::create`], so a key always
+    /// produces the same points for the same base seed.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn create_scatter(&self, key: u32, method: ScatterMethod) -> Scatter {
+        Scatter::new(hash_combine(self.seed, key), method)
+    }
+
+    /// Create integer-only noise with a derived seed.
+    ///
+    /// Uses the same key derivation as [`Self::create`], and keeps the
+    /// source's output version.
+    #[must_use]
+    pub fn create_fixed(&self, key: u32) -> FixedNoise {
+        FixedNoise::new(hash_combine(self.seed, key)).with_output_version(self.output_version)
     }
 }
 
@@ -175,10 +453,28 @@ fn hash_combine(a: u32, b: u32) -> u32 {
     h
 }
 
+/// Run a whole image, statistics or mesh build, timing it for the
+/// diagnostics plugin when enabled.
+#[cfg(feature = "std")]
+#[inline]
+fn timed<T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "bevy")]
+    return diagnostics::timed(f);
+    #[cfg(not(feature = "bevy"))]
+    f()
+}
+
 /// A configurable Perlin noise generator.
 ///
 /// Create instances via [`NoiseSource::create`] rather than directly.
 ///
+/// Input coordinates are mapped into noise space by an input transform
+/// before sampling: the custom affine transform is applied first, then
+/// the offset is added, the result is rotated, and finally each axis is
+/// multiplied by its scale. 2D samplers transform `(x, y, 0)` and use the
+/// resulting `x` and `y`, so rotations out of the XY plane are only
+/// meaningful for 3D sampling.
+///
 /// # Examples
 ///
 /// ```rust
@@ -193,10 +489,18 @@ fn hash_combine(a: u32, b: u32) -> u32 {
 #[derive(Clone)]
 pub struct Noise {
     generator: ScalePoint<Perlin>,
-    scale: f64,
-    offset: f64,
+    seed: u32,
+    backend: NoiseBackend,
+    output_version: u32,
+    scale: DVec3,
+    offset: DVec3,
+    rotation: DQuat,
+    transform: DAffine3,
     range_min: f64,
     range_max: f64,
+    curve: RemapCurve,
+    clamp: bool,
+    calibration: Option<Arc<NoiseCalibration>>,
 }
 
 impl Default for Noise {
@@ -208,10 +512,18 @@ impl Default for Noise {
 impl fmt::Debug for Noise {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         f.debug_struct("Noise")
+            .field("seed", &self.seed)
+            .field("backend", &self.backend)
+            .field("output_version", &self.output_version)
             .field("scale", &self.scale)
             .field("offset", &self.offset)
+            .field("rotation", &self.rotation)
+            .field("transform", &self.transform)
             .field("range_min", &self.range_min)
             .field("range_max", &self.range_max)
+            .field("curve", &self.curve)
+            .field("clamp", &self.clamp)
+            .field("calibration", &self.calibration)
             .finish_non_exhaustive()
     }
 }
@@ -222,10 +534,18 @@ impl Noise {
     pub fn new(seed: u32) -> Self {
         Self {
             generator: ScalePoint::new(Perlin::new(seed)),
-            scale: DEFAULT_NOISE_SCALE,
-            offset: 0.0,
+            seed,
+            backend: NoiseBackend::Perlin,
+            output_version: NOISE_OUTPUT_VERSION,
+            scale: DVec3::splat(DEFAULT_NOISE_SCALE),
+            offset: DVec3::ZERO,
+            rotation: DQuat::IDENTITY,
+            transform: DAffine3::IDENTITY,
             range_min: 0.0,
             range_max: 1.0,
+            curve: RemapCurve::Linear,
+            clamp: false,
+            calibration: None,
         }
     }
 
@@ -238,17 +558,74 @@ impl Noise {
         Self::new(combined)
     }
 
+    /// Set the algorithm used to evaluate the noise.
+    ///
+    /// Use [`NoiseBackend::Deterministic`] when every machine must produce
+    /// bit-identical values, e.g. for lockstep multiplayer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use msg_noise::{Noise, NoiseBackend};
+    ///
+    /// let terrain = Noise::new(12345).with_backend(NoiseBackend::Deterministic);
+    /// let height = terrain.get_fractal(10.0, 20.0, 4, 0.5, 2.0);
+    /// ```
+    #[must_use]
+    pub fn with_backend(mut self, backend: NoiseBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Get the evaluation algorithm.
+    #[must_use]
+    pub fn backend(&self) -> NoiseBackend {
+        self.backend
+    }
+
+    /// Produce the output of an earlier [`NOISE_OUTPUT_VERSION`].
+    ///
+    /// Generators created by a [`NoiseSource`] inherit its version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` is not between 1 and [`NOISE_OUTPUT_VERSION`].
+    #[must_use]
+    #[track_caller]
+    pub fn with_output_version(mut self, version: u32) -> Self {
+        self.output_version = UnsupportedOutputVersion::expect(version);
+        self
+    }
+
+    /// Get the output version.
+    #[must_use]
+    pub fn output_version(&self) -> u32 {
+        self.output_version
+    }
+
     /// Set the noise scale (frequency).
     ///
     /// Lower values create smoother, larger features.
     /// Higher values create noisier, smaller features.
     #[must_use]
     pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = DVec3::splat(scale);
+        self
+    }
+
+    /// Set a separate scale (frequency) for each axis.
+    ///
+    /// Use a smaller scale on one axis to stretch features along it,
+    /// e.g. wide horizontal strata.
+    #[must_use]
+    pub fn with_axis_scale(mut self, scale: DVec3) -> Self {
         self.scale = scale;
         self
     }
 
     /// Set the output range for scaled values.
+    ///
+    /// `min` may be greater than `max` to invert the output.
     #[must_use]
     pub fn with_range(mut self, min: f64, max: f64) -> Self {
         self.range_min = min;
@@ -256,30 +633,200 @@ impl Noise {
         self
     }
 
+    /// Set the curve applied to normalized values before range mapping.
+    #[must_use]
+    pub fn with_curve(mut self, curve: RemapCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Clamp scaled values to the configured range.
+    ///
+    /// Raw Perlin noise can slightly exceed -1.0 to 1.0 and custom curves
+    /// may leave 0.0 to 1.0, so scaled values are not clamped by default.
+    #[must_use]
+    pub fn with_clamp(mut self, clamp: bool) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
     /// Set an offset to add to the noise coordinates.
     #[must_use]
     pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = DVec3::splat(offset);
+        self
+    }
+
+    /// Set a separate offset for each axis.
+    #[must_use]
+    pub fn with_axis_offset(mut self, offset: DVec3) -> Self {
         self.offset = offset;
         self
     }
 
+    /// Set a rotation applied to the offset coordinates before scaling.
+    ///
+    /// Rotating noise breaks up the axis-aligned artifacts of Perlin noise
+    /// and lets stretched features run in any direction.
+    #[must_use]
+    pub fn with_rotation(mut self, rotation: DQuat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Set an arbitrary affine transform applied to the input coordinates
+    /// before the offset, rotation and scale.
+    #[must_use]
+    pub fn with_transform(mut self, transform: DAffine3) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Attach a measured calibration used by the calibrated and equalized
+    /// samplers.
+    #[must_use]
+    pub fn with_calibration(mut self, calibration: NoiseCalibration) -> Self {
+        self.calibration = Some(Arc::new(calibration));
+        self
+    }
+
+    /// Measure and attach a calibration for the current configuration.
+    ///
+    /// Call this after configuring scale and transform, since the
+    /// calibration samples the noise as currently configured.
+    #[must_use]
+    pub fn calibrated(self, settings: &CalibrationSettings) -> Self {
+        let calibration = NoiseCalibration::measure(&self, settings);
+        self.with_calibration(calibration)
+    }
+
+    /// Get the attached calibration, if any.
+    #[must_use]
+    pub fn calibration(&self) -> Option<&NoiseCalibration> {
+        self.calibration.as_deref()
+    }
+
+    /// Map input coordinates into noise space.
+    #[inline]
+    fn to_noise_space(&self, point: DVec3) -> DVec3 {
+        let point = self.transform.transform_point3(point) + self.offset;
+        (self.rotation * point) * self.scale
+    }
+
     /// Get raw Perlin noise value (typically between -1.0 and 1.0).
     #[must_use]
     pub fn get_raw(&self, x: f64, y: f64) -> f64 {
-        self.generator.get([
-            (x + self.offset) * self.scale,
-            (y + self.offset) * self.scale,
-        ])
+        let point = self.to_noise_space(DVec3::new(x, y, 0.0));
+        self.evaluate([point.x, point.y])
+    }
+
+    /// Get raw 1D noise value (typically between -1.0 and 1.0).
+    ///
+    /// Samples a line through the 2D noise field, so the input transform
+    /// applies to `(t, 0, 0)` as it would for [`Self::get_raw`].
+    #[must_use]
+    pub fn get_raw_1d(&self, t: f64) -> f64 {
+        let point = self.to_noise_space(DVec3::new(t, 0.0, 0.0));
+        self.evaluate([point.x, point.y + LINE_Y])
     }
 
     /// Get raw 3D Perlin noise value.
     #[must_use]
     pub fn get_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
-        self.generator.get([
-            (x + self.offset) * self.scale,
-            (y + self.offset) * self.scale,
-            (z + self.offset) * self.scale,
-        ])
+        let point = self.to_noise_space(DVec3::new(x, y, z));
+        self.evaluate(point.to_array())
+    }
+
+    /// Evaluate the generator, counting the sample when diagnostics are
+    /// enabled.
+    #[inline]
+    fn evaluate<const DIM: usize>(&self, point: [f64; DIM]) -> f64
+    where
+        ScalePoint<Perlin>: NoiseFn<f64, DIM>,
+    {
+        #[cfg(feature = "bevy")]
+        diagnostics::record_sample();
+        self.evaluate_backend(point)
+    }
+
+    /// Evaluate the configured backend at a point in noise space.
+    #[inline]
+    fn evaluate_backend<const DIM: usize>(&self, point: [f64; DIM]) -> f64
+    where
+        ScalePoint<Perlin>: NoiseFn<f64, DIM>,
+    {
+        // When NOISE_OUTPUT_VERSION is bumped, keep the previous algorithm
+        // under its version here so saved seeds reproduce their worlds.
+        match self.output_version {
+            1 => match self.backend {
+                NoiseBackend::Perlin => self.generator.get(point),
+                NoiseBackend::Deterministic => deterministic::gradient_noise(self.seed, point),
+            },
+            version => unreachable!("{}", UnsupportedOutputVersion(version)),
+        }
+    }
+
+    /// Analytic gradient of the sampler selected by `mode` with respect to
+    /// the input coordinates, evaluated in `DIM` dimensions at `input`.
+    ///
+    /// Only the deterministic backend exposes derivatives, for the raw,
+    /// normalized and fractal samplers. Returns `None` otherwise.
+    #[cfg(feature = "std")]
+    pub(crate) fn analytic_gradient<const DIM: usize>(
+        &self,
+        mode: SampleMode,
+        input: DVec3,
+    ) -> Option<DVec3> {
+        match mode {
+            SampleMode::Raw => self.raw_gradient::<DIM>(input),
+            SampleMode::Normalized => self.raw_gradient::<DIM>(input).map(|g| g * 0.5),
+            SampleMode::Fractal {
+                octaves,
+                persistence,
+                lacunarity,
+            } => {
+                // Mirrors get_fractal and get_fractal_3d
+                let mut gradient = DVec3::ZERO;
+                let mut amplitude = 1.0;
+                let mut frequency = 1.0;
+                let mut max_value = 0.0;
+                for _ in 0..octaves {
+                    gradient +=
+                        self.raw_gradient::<DIM>(input * frequency)? * (amplitude * frequency);
+                    max_value += amplitude;
+                    amplitude *= persistence;
+                    frequency *= lacunarity;
+                }
+                Some(gradient / max_value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Analytic gradient of the raw sampler, if the backend has one.
+    #[cfg(feature = "std")]
+    fn raw_gradient<const DIM: usize>(&self, input: DVec3) -> Option<DVec3> {
+        if self.backend != NoiseBackend::Deterministic || self.output_version != 1 {
+            return None;
+        }
+        #[cfg(feature = "bevy")]
+        diagnostics::record_sample();
+        let point = self.to_noise_space(input);
+        let gradient = deterministic::gradient_noise_gradient::<DIM>(
+            self.seed,
+            core::array::from_fn(|axis| point[axis]),
+        );
+        let mut noise_gradient = DVec3::ZERO;
+        for (axis, component) in gradient.into_iter().enumerate() {
+            noise_gradient[axis] = component;
+        }
+        // Chain rule through the scale, rotation and input transform
+        Some(
+            self.transform
+                .matrix3
+                .transpose()
+                .mul_vec3(self.rotation.inverse() * (noise_gradient * self.scale)),
+        )
     }
 
     /// Get absolute noise value (0.0 to 1.0).
@@ -294,12 +841,101 @@ impl Noise {
         (self.get_raw(x, y) + 1.0) * 0.5
     }
 
+    /// Get normalized 1D noise value (0.0 to 1.0).
+    #[must_use]
+    pub fn get_normalized_1d(&self, t: f64) -> f64 {
+        (self.get_raw_1d(t) + 1.0) * 0.5
+    }
+
     /// Get normalized 3D noise value (0.0 to 1.0).
     #[must_use]
     pub fn get_normalized_3d(&self, x: f64, y: f64, z: f64) -> f64 {
         (self.get_raw_3d(x, y, z) + 1.0) * 0.5
     }
 
+    /// Get noise scaled to the configured range.
+    #[must_use]
+    pub fn get_scaled(&self, x: f64, y: f64) -> f64 {
+        self.map_to_range(self.get_normalized(x, y))
+    }
+
+    /// Get 1D noise scaled to the configured range.
+    #[must_use]
+    pub fn get_scaled_1d(&self, t: f64) -> f64 {
+        self.map_to_range(self.get_normalized_1d(t))
+    }
+
+    /// Get 3D noise scaled to the configured range.
+    #[must_use]
+    pub fn get_scaled_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.map_to_range(self.get_normalized_3d(x, y, z))
+    }
+
+    /// Get absolute noise scaled to the configured range.
+    #[must_use]
+    pub fn get_absolute_scaled(&self, x: f64, y: f64) -> f64 {
+        self.map_to_range(self.get_absolute(x, y))
+    }
+
+    /// Get noise normalized to the calibrated range (0.0 to 1.0).
+    ///
+    /// Without a calibration this is equivalent to [`Self::get_normalized`].
+    #[must_use]
+    pub fn get_calibrated(&self, x: f64, y: f64) -> f64 {
+        self.normalize_calibrated(self.get_raw(x, y))
+    }
+
+    /// Get 3D noise normalized to the calibrated range (0.0 to 1.0).
+    #[must_use]
+    pub fn get_calibrated_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.normalize_calibrated(self.get_raw_3d(x, y, z))
+    }
+
+    /// Get approximately uniformly distributed noise (0.0 to 1.0).
+    ///
+    /// Values are mapped through the calibration's cumulative distribution,
+    /// so a threshold of 0.3 covers roughly 30% of the area. Without a
+    /// calibration this is equivalent to [`Self::get_normalized`].
+    #[must_use]
+    pub fn get_equalized(&self, x: f64, y: f64) -> f64 {
+        self.equalize_calibrated(self.get_raw(x, y))
+    }
+
+    /// Get approximately uniformly distributed 3D noise (0.0 to 1.0).
+    #[must_use]
+    pub fn get_equalized_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.equalize_calibrated(self.get_raw_3d(x, y, z))
+    }
+
+    #[inline]
+    fn normalize_calibrated(&self, raw: f64) -> f64 {
+        match &self.calibration {
+            Some(calibration) => calibration.normalize(raw),
+            None => (raw + 1.0) * 0.5,
+        }
+    }
+
+    #[inline]
+    fn equalize_calibrated(&self, raw: f64) -> f64 {
+        match &self.calibration {
+            Some(calibration) => calibration.equalize(raw),
+            None => (raw + 1.0) * 0.5,
+        }
+    }
+
+    /// Map a normalized value (0.0 to 1.0) to the configured range.
+    ///
+    /// The remap curve is applied first, then the value is interpolated
+    /// between the range bounds and clamped if clamping is enabled.
+    #[must_use]
+    pub fn map_to_range(&self, normalized: f64) -> f64 {
+        let mut t = self.curve.apply(normalized);
+        if self.clamp {
+            t = t.clamp(0.0, 1.0);
+        }
+        self.range_min + t * (self.range_max - self.range_min)
+    }
+
     /// Generate fractal noise by combining multiple octaves.
     #[must_use]
     pub fn get_fractal(
@@ -325,6 +961,50 @@ impl Noise {
         value / max_value
     }
 
+    /// Generate 1D fractal noise by combining multiple octaves.
+    #[must_use]
+    pub fn get_fractal_1d(&self, t: f64, octaves: u32, persistence: f64, lacunarity: f64) -> f64 {
+        let mut value = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut max_value = 0.0;
+
+        for _ in 0..octaves {
+            value += self.get_raw_1d(t * frequency) * amplitude;
+            max_value += amplitude;
+            amplitude *= persistence;
+            frequency *= lacunarity;
+        }
+
+        value / max_value
+    }
+
+    /// Generate 3D fractal noise by combining multiple octaves.
+    #[must_use]
+    pub fn get_fractal_3d(
+        &self,
+        x: f64,
+        y: f64,
+        z: f64,
+        octaves: u32,
+        persistence: f64,
+        lacunarity: f64,
+    ) -> f64 {
+        let mut value = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut max_value = 0.0;
+
+        for _ in 0..octaves {
+            value += self.get_raw_3d(x * frequency, y * frequency, z * frequency) * amplitude;
+            max_value += amplitude;
+            amplitude *= persistence;
+            frequency *= lacunarity;
+        }
+
+        value / max_value
+    }
+
     /// Get fractal noise scaled to the configured range.
     #[must_use]
     pub fn get_fractal_scaled(
@@ -336,12 +1016,48 @@ impl Noise {
         lacunarity: f64,
     ) -> f64 {
         let fractal = self.get_fractal(x, y, octaves, persistence, lacunarity);
-        let normalized = (fractal + 1.0) * 0.5;
-        self.range_min + normalized * (self.range_max - self.range_min)
+        self.map_to_range((fractal + 1.0) * 0.5)
+    }
+
+    /// Get fractal noise normalized to the calibrated range (0.0 to 1.0).
+    ///
+    /// The calibration should be measured with
+    /// [`CalibrationSampler::Fractal`] using the same parameters.
+    #[must_use]
+    pub fn get_fractal_calibrated(
+        &self,
+        x: f64,
+        y: f64,
+        octaves: u32,
+        persistence: f64,
+        lacunarity: f64,
+    ) -> f64 {
+        self.normalize_calibrated(self.get_fractal(x, y, octaves, persistence, lacunarity))
+    }
+
+    /// Get approximately uniformly distributed fractal noise (0.0 to 1.0).
+    ///
+    /// The calibration should be measured with
+    /// [`CalibrationSampler::Fractal`] using the same parameters.
+    #[must_use]
+    pub fn get_fractal_equalized(
+        &self,
+        x: f64,
+        y: f64,
+        octaves: u32,
+        persistence: f64,
+        lacunarity: f64,
+    ) -> f64 {
+        self.equalize_calibrated(self.get_fractal(x, y, octaves, persistence, lacunarity))
     }
 
     /// Update the internal scale.
     pub fn set_scale(&mut self, scale: f64) {
+        self.scale = DVec3::splat(scale);
+    }
+
+    /// Update the per-axis scale.
+    pub fn set_axis_scale(&mut self, scale: DVec3) {
         self.scale = scale;
     }
 
@@ -351,20 +1067,93 @@ impl Noise {
         self.range_max = max;
     }
 
+    /// Update the remap curve.
+    pub fn set_curve(&mut self, curve: RemapCurve) {
+        self.curve = curve;
+    }
+
+    /// Enable or disable clamping of scaled values.
+    pub fn set_clamp(&mut self, clamp: bool) {
+        self.clamp = clamp;
+    }
+
     /// Update the coordinate offset.
     pub fn set_offset(&mut self, offset: f64) {
+        self.offset = DVec3::splat(offset);
+    }
+
+    /// Update the per-axis coordinate offset.
+    pub fn set_axis_offset(&mut self, offset: DVec3) {
         self.offset = offset;
     }
+
+    /// Update the input rotation.
+    pub fn set_rotation(&mut self, rotation: DQuat) {
+        self.rotation = rotation;
+    }
+
+    /// Update the custom affine input transform.
+    pub fn set_transform(&mut self, transform: DAffine3) {
+        self.transform = transform;
+    }
+
+    /// Replace or remove the attached calibration.
+    pub fn set_calibration(&mut self, calibration: Option<NoiseCalibration>) {
+        self.calibration = calibration.map(Arc::new);
+    }
+
+    /// Update the evaluation algorithm.
+    pub fn set_backend(&mut self, backend: NoiseBackend) {
+        self.backend = backend;
+    }
+
+    /// Update the output version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` is not between 1 and [`NOISE_OUTPUT_VERSION`].
+    #[track_caller]
+    pub fn set_output_version(&mut self, version: u32) {
+        self.output_version = UnsupportedOutputVersion::expect(version);
+    }
 }
 
 /// Prelude module for convenient imports.
 pub mod prelude {
-    pub use super::{Noise, NoisePlugin, NoiseSource};
+    pub use super::{
+        CalibrationSampler, CalibrationSettings, CustomNoise, FixedNoise, FixedNoiseKind, Noise,
+        NoiseBackend, NoiseCalibration, NoiseSampler, NoiseSource, RemapCurve, SampleMode,
+        SampleRegion,
+    };
+
+    #[cfg(feature = "std")]
+    pub use super::{
+        Biome, BiomeGrid, BiomeMap, BiomeSample, Contour, ContourBuilder, ContourLevel,
+        CurlNoise2D, CurlNoise3D, FlowField, LayerBlend, NoiseLayer, NoiseLayers, NoiseSignal,
+        NoiseStats, Placement, PlacementMask, PlacementRule, PlacementRules, Scatter,
+        ScatterMethod, StatsRegion, StatsSettings, TimeNoise,
+    };
+
+    #[cfg(feature = "bevy")]
+    pub use super::{NoiseDiagnosticsPlugin, NoiseDiagnosticsRecorder, NoisePlugin};
+
+    #[cfg(feature = "image")]
+    pub use super::{ColorRamp, NoiseImage, NoiseImageFormat, TimeNoiseImage};
+
+    #[cfg(feature = "preview")]
+    pub use super::{NoisePreview, NoisePreviewPlugin, NoiseRecipe};
+
+    #[cfg(feature = "mesh")]
+    pub use super::{
+        ChunkEdge, HeightmapMeshBuilder, HeightmapNormals, Isosurface, IsosurfaceBuilder,
+        IsosurfaceMethod,
+    };
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec::Vec;
 
     #[test]
     fn seeded_noise_is_deterministic() {
@@ -466,4 +1255,41 @@ mod tests {
         // Rougher noise should have larger differences between nearby points
         assert!(rough_diff > smooth_diff);
     }
+
+    #[test]
+    fn axis_offset_shifts_each_axis_independently() {
+        let shifted = Noise::new(42).with_axis_offset(DVec3::new(100.0, -50.0, 7.0));
+        let plain = Noise::new(42);
+
+        for i in 0..10 {
+            let x = f64::from(i);
+            assert!(
+                (shifted.get_raw_3d(x, x, x) - plain.get_raw_3d(x + 100.0, x - 50.0, x + 7.0))
+                    .abs()
+                    < 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn axis_scale_stretches_single_axis() {
+        let stretched = Noise::new(42).with_axis_scale(DVec3::new(0.1, 0.0, 0.1));
+
+        // With zero frequency on y, moving along y must not change the value
+        let a = stretched.get_raw(3.7, 0.0);
+        let b = stretched.get_raw(3.7, 1234.5);
+        assert!((a - b).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn one_d_noise_is_not_pinned_to_lattice() {
+        let noise = Noise::new(42).with_scale(1.0);
+
+        // Integer inputs land on the lattice, where 2D noise along y = 0 is zero
+        let nonzero = (0..10)
+            .filter(|i| noise.get_raw_1d(f64::from(*i)).abs() > 1e-6)
+            .count();
+        assert!(nonzero > 5);
+        assert!((noise.get_fractal_1d(0.3, 1, 0.5, 2.0) - noise.get_raw_1d(0.3)).abs() < 1e-12);
+    }
 }
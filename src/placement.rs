@@ -0,0 +1,425 @@
+//! Rule-based object placement on top of [`Scatter`](crate::Scatter).
+//!
+//! A [`PlacementRule`] scatters candidate points, filters them through
+//! noise-based [`PlacementMask`]s and assigns each survivor a deterministic
+//! random rotation and scale. Everything is derived from the
+//! [`NoiseSource`] seed, the rule key and the point position, so reloading
+//! a chunk always yields the same props.
+
+use crate::scatter::TileRng;
+use crate::{Noise, NoiseSource, SampleMode, SampleRegion, ScatterMethod};
+#[cfg(feature = "bevy")]
+use bevy::transform::components::Transform;
+use glam::{DVec2, DVec3};
+#[cfg(feature = "bevy")]
+use glam::{Quat, Vec3};
+use std::f64::consts::TAU;
+use std::fmt;
+use std::sync::Arc;
+
+/// Distance, in world units, used for central differences when measuring
+/// slope.
+const SLOPE_EPSILON: f64 = 0.1;
+
+/// Salt separating placement randomness from the scatter positions.
+const PLACEMENT_SALT: u32 = 0x504C_4143;
+
+/// A condition a candidate point must meet to receive a placement.
+///
+/// Points are world `(x, z)` positions on the ground plane.
+#[derive(Clone)]
+pub enum PlacementMask {
+    /// The sampled value lies within `min..=max`. Use for height bands,
+    /// moisture thresholds and similar layers.
+    Band {
+        /// Layer to sample.
+        noise: Noise,
+        /// Sampler to evaluate.
+        mode: SampleMode,
+        /// Smallest allowed value.
+        min: f64,
+        /// Largest allowed value.
+        max: f64,
+    },
+    /// The terrain slope, in radians, lies within `min_angle..=max_angle`.
+    Slope {
+        /// Height layer to sample.
+        noise: Noise,
+        /// Sampler to evaluate.
+        mode: SampleMode,
+        /// Multiplier converting sampled values into world heights.
+        height_scale: f64,
+        /// Smallest allowed slope.
+        min_angle: f64,
+        /// Largest allowed slope.
+        max_angle: f64,
+    },
+    /// An arbitrary user-provided condition.
+    Custom(Arc<dyn Fn(DVec2) -> bool + Send + Sync>),
+}
+
+impl PlacementMask {
+    /// Allow points where `noise` sampled with `mode` lies within
+    /// `min..=max`.
+    #[must_use]
+    pub fn band(noise: &Noise, mode: SampleMode, min: f64, max: f64) -> Self {
+        Self::Band {
+            noise: noise.clone(),
+            mode,
+            min,
+            max,
+        }
+    }
+
+    /// Allow points where the slope of the terrain described by `noise`,
+    /// `mode` and `height_scale` lies within `min_angle..=max_angle`
+    /// radians.
+    #[must_use]
+    pub fn slope(
+        noise: &Noise,
+        mode: SampleMode,
+        height_scale: f64,
+        min_angle: f64,
+        max_angle: f64,
+    ) -> Self {
+        Self::Slope {
+            noise: noise.clone(),
+            mode,
+            height_scale,
+            min_angle,
+            max_angle,
+        }
+    }
+
+    /// Create a mask from an arbitrary condition.
+    #[must_use]
+    pub fn custom(f: impl Fn(DVec2) -> bool + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(f))
+    }
+
+    /// Whether the mask allows a placement at `point`.
+    #[must_use]
+    pub fn allows(&self, point: DVec2) -> bool {
+        match self {
+            Self::Band {
+                noise,
+                mode,
+                min,
+                max,
+            } => (*min..=*max).contains(&noise.sample(*mode, point.x, point.y)),
+            Self::Slope {
+                noise,
+                mode,
+                height_scale,
+                min_angle,
+                max_angle,
+            } => {
+                let height = |x, z| noise.sample(*mode, x, z) * height_scale;
+                let e = SLOPE_EPSILON;
+                let gradient = DVec2::new(
+                    height(point.x + e, point.y) - height(point.x - e, point.y),
+                    height(point.x, point.y + e) - height(point.x, point.y - e),
+                ) / (2.0 * e);
+                (*min_angle..=*max_angle).contains(&gradient.length().atan())
+            }
+            Self::Custom(f) => f(point),
+        }
+    }
+}
+
+impl fmt::Debug for PlacementMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::Band {
+                noise,
+                mode,
+                min,
+                max,
+            } => f
+                .debug_struct("Band")
+                .field("noise", noise)
+                .field("mode", mode)
+                .field("min", min)
+                .field("max", max)
+                .finish(),
+            Self::Slope {
+                noise,
+                mode,
+                height_scale,
+                min_angle,
+                max_angle,
+            } => f
+                .debug_struct("Slope")
+                .field("noise", noise)
+                .field("mode", mode)
+                .field("height_scale", height_scale)
+                .field("min_angle", min_angle)
+                .field("max_angle", max_angle)
+                .finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// A placed object.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Placement {
+    /// Key of the rule that produced the placement.
+    pub key: u32,
+    /// World position, with the ground height in `y`.
+    pub position: DVec3,
+    /// Rotation around the y axis in radians.
+    pub yaw: f64,
+    /// Uniform scale.
+    pub scale: f64,
+}
+
+impl Placement {
+    /// Build a [`Transform`] for spawning the object.
+    #[cfg(feature = "bevy")]
+    #[must_use]
+    pub fn transform(&self) -> Transform {
+        Transform::from_translation(self.position.as_vec3())
+            .with_rotation(Quat::from_rotation_y(self.yaw as f32))
+            .with_scale(Vec3::splat(self.scale as f32))
+    }
+}
+
+/// A rule producing placements for one kind of object.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::math::DVec2;
+/// use msg_noise::{NoiseSource, PlacementMask, PlacementRule, SampleMode, SampleRegion, ScatterMethod};
+///
+/// let source = NoiseSource::new(12345);
+/// let height = source.create(0x4845_4947).with_scale(0.01);
+/// let moisture = source.create(0x4D4F_4953).with_scale(0.02);
+///
+/// let trees = PlacementRule::new(0x5452_4545, ScatterMethod::Poisson { radius: 4.0 })
+///     .with_density(0.8)
+///     .with_mask(PlacementMask::band(&height, SampleMode::Normalized, 0.3, 0.7))
+///     .with_mask(PlacementMask::band(&moisture, SampleMode::Normalized, 0.5, 1.0))
+///     .with_mask(PlacementMask::slope(&height, SampleMode::Normalized, 40.0, 0.0, 0.5))
+///     .with_ground(&height, SampleMode::Normalized, 40.0)
+///     .with_scale_range(0.8, 1.2);
+///
+/// let chunk = SampleRegion::new(DVec2::ZERO, DVec2::splat(64.0));
+/// for placement in trees.place(&source, chunk) {
+///     let (position, yaw) = (placement.position, placement.yaw);
+///     // spawn a tree at `position` rotated by `yaw`
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PlacementRule {
+    key: u32,
+    method: ScatterMethod,
+    density: f64,
+    masks: Vec<PlacementMask>,
+    ground: Option<(Noise, SampleMode, f64)>,
+    random_yaw: bool,
+    scale_range: (f64, f64),
+}
+
+impl PlacementRule {
+    /// Create a rule scattering candidate points with `method`. The key
+    /// derives the rule's seed, as in [`NoiseSource::create`].
+    #[must_use]
+    pub fn new(key: u32, method: ScatterMethod) -> Self {
+        Self {
+            key,
+            method,
+            density: 1.0,
+            masks: Vec::new(),
+            ground: None,
+            random_yaw: true,
+            scale_range: (1.0, 1.0),
+        }
+    }
+
+    /// Set the fraction of candidate points that receive a placement.
+    #[must_use]
+    pub fn with_density(mut self, density: f64) -> Self {
+        self.density = density.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Add a mask. Points must pass every mask.
+    #[must_use]
+    pub fn with_mask(mut self, mask: PlacementMask) -> Self {
+        self.masks.push(mask);
+        self
+    }
+
+    /// Place objects on the terrain height `noise` sampled with `mode`,
+    /// multiplied by `height_scale`. Without a ground, placements sit at
+    /// `y = 0`.
+    #[must_use]
+    pub fn with_ground(mut self, noise: &Noise, mode: SampleMode, height_scale: f64) -> Self {
+        self.ground = Some((noise.clone(), mode, height_scale));
+        self
+    }
+
+    /// Set whether placements get a random rotation around the y axis.
+    /// Enabled by default.
+    #[must_use]
+    pub fn with_random_yaw(mut self, random_yaw: bool) -> Self {
+        self.random_yaw = random_yaw;
+        self
+    }
+
+    /// Set the range of random uniform scales. Defaults to `1.0..=1.0`.
+    #[must_use]
+    pub fn with_scale_range(mut self, min: f64, max: f64) -> Self {
+        self.scale_range = (min, max);
+        self
+    }
+
+    /// Get the rule key.
+    #[must_use]
+    pub fn key(&self) -> u32 {
+        self.key
+    }
+
+    /// Placements inside `region`, using the same half-open bounds as
+    /// [`Scatter::points`](crate::Scatter::points).
+    #[must_use]
+    pub fn place(&self, source: &NoiseSource, region: SampleRegion) -> Vec<Placement> {
+        let scatter = source.create_scatter(self.key, self.method.clone());
+        let seed = scatter.seed() ^ PLACEMENT_SALT;
+
+        scatter
+            .points(region)
+            .into_iter()
+            .filter_map(|point| {
+                let mut rng = TileRng::at_point(seed, point);
+                let keep = rng.next_f64() < self.density;
+                let yaw = rng.next_f64() * TAU;
+                let scale = rng.next_f64();
+                if !keep || !self.masks.iter().all(|mask| mask.allows(point)) {
+                    return None;
+                }
+
+                let height = self.ground.as_ref().map_or(0.0, |(noise, mode, scale)| {
+                    noise.sample(*mode, point.x, point.y) * scale
+                });
+                let (min_scale, max_scale) = self.scale_range;
+                Some(Placement {
+                    key: self.key,
+                    position: DVec3::new(point.x, height, point.y),
+                    yaw: if self.random_yaw { yaw } else { 0.0 },
+                    scale: min_scale + (max_scale - min_scale) * scale,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A set of [`PlacementRule`]s evaluated together.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::math::DVec2;
+/// use msg_noise::{NoiseSource, PlacementRule, PlacementRules, SampleRegion, ScatterMethod};
+///
+/// let rules = PlacementRules::new()
+///     .with_rule(PlacementRule::new(0x5452_4545, ScatterMethod::Poisson { radius: 4.0 }))
+///     .with_rule(PlacementRule::new(0x524F_434B, ScatterMethod::Poisson { radius: 9.0 }));
+///
+/// let source = NoiseSource::new(12345);
+/// let placements = rules.place(&source, SampleRegion::new(DVec2::ZERO, DVec2::splat(64.0)));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PlacementRules {
+    rules: Vec<PlacementRule>,
+}
+
+impl PlacementRules {
+    /// Create an empty rule set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule.
+    #[must_use]
+    pub fn with_rule(mut self, rule: PlacementRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Add a rule.
+    pub fn add_rule(&mut self, rule: PlacementRule) {
+        self.rules.push(rule);
+    }
+
+    /// Get the rules.
+    #[must_use]
+    pub fn rules(&self) -> &[PlacementRule] {
+        &self.rules
+    }
+
+    /// Placements of every rule inside `region`, in rule order.
+    #[must_use]
+    pub fn place(&self, source: &NoiseSource, region: SampleRegion) -> Vec<Placement> {
+        self.rules
+            .iter()
+            .flat_map(|rule| rule.place(source, region))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region() -> SampleRegion {
+        SampleRegion::new(DVec2::ZERO, DVec2::splat(64.0))
+    }
+
+    #[test]
+    fn density_thins_placements() {
+        let source = NoiseSource::new(42);
+        let rule = PlacementRule::new(1, ScatterMethod::Poisson { radius: 2.0 });
+
+        let all = rule.place(&source, region()).len();
+        let half = rule
+            .clone()
+            .with_density(0.5)
+            .place(&source, region())
+            .len();
+
+        assert!(half > all / 3 && half < all * 2 / 3);
+    }
+
+    #[test]
+    fn slope_mask_uses_gradient() {
+        let noise = Noise::new(1).with_scale(0.05);
+        let point = DVec2::new(3.3, 4.7);
+
+        // Flattened terrain has no slope, exaggerated terrain is nearly vertical
+        let flat = |min, max| PlacementMask::slope(&noise, SampleMode::Normalized, 0.0, min, max);
+        let steep = |min, max| PlacementMask::slope(&noise, SampleMode::Normalized, 1e4, min, max);
+        assert!(flat(0.0, 0.1).allows(point));
+        assert!(!flat(0.1, 1.6).allows(point));
+        assert!(steep(1.0, 1.6).allows(point));
+        assert!(!steep(0.0, 1.0).allows(point));
+    }
+
+    #[test]
+    fn scale_and_yaw_stay_in_range() {
+        let source = NoiseSource::new(42);
+        let placements = PlacementRule::new(1, ScatterMethod::Poisson { radius: 2.0 })
+            .with_scale_range(0.5, 2.0)
+            .place(&source, region());
+
+        assert!(!placements.is_empty());
+        for placement in placements {
+            assert!((0.5..=2.0).contains(&placement.scale));
+            assert!((0.0..TAU).contains(&placement.yaw));
+            assert!(placement.position.y.abs() < f64::EPSILON);
+        }
+    }
+}
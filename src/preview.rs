@@ -0,0 +1,568 @@
+//! Live previews for tuning noise parameters.
+//!
+//! A [`NoisePreview`] component renders a [`NoiseRecipe`] into an image
+//! and a histogram image whenever its reflected settings change, e.g. from
+//! an inspector. Show the images on a UI node or sprite. Rendering itself
+//! is plain [`NoisePreview::render`], so it works headlessly.
+
+use crate::{
+    ColorRamp, DEFAULT_NOISE_SCALE, NOISE_OUTPUT_VERSION, Noise, NoiseImage, NoiseImageFormat,
+    NoiseSource, SampleRegion,
+};
+use bevy::prelude::*;
+use glam::{DVec2, UVec2};
+use serde::{Deserialize, Serialize};
+
+/// Height of histogram images in pixels.
+const HISTOGRAM_HEIGHT: u32 = 64;
+
+/// Largest fractal octave count; more octaves add no visible detail.
+const MAX_OCTAVES: u32 = 32;
+
+/// Default width and height of the previewed region in input units.
+const DEFAULT_EXTENT: f64 = 256.0;
+
+/// Largest preview width and height in pixels.
+const MAX_RESOLUTION: u32 = 4096;
+
+/// Largest number of histogram bins.
+const MAX_HISTOGRAM_BINS: u32 = 1024;
+
+/// Parameters of a fractal noise, exportable as code or RON.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{NoiseRecipe, NoiseSource};
+///
+/// let recipe = NoiseRecipe::new(0x5445_5252)
+///     .with_scale(0.02)
+///     .with_fractal(4, 0.5, 2.0)
+///     .with_range(0.0, 100.0);
+///
+/// let ron = recipe.to_ron();
+/// assert_eq!(NoiseRecipe::from_ron(&ron).unwrap(), recipe);
+/// let code = recipe.to_code(&NoiseSource::new(12345));
+/// assert!(code.contains(".create(0x5445_5252)"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseRecipe {
+    key: u32,
+    scale: f64,
+    octaves: u32,
+    persistence: f64,
+    lacunarity: f64,
+    range: (f64, f64),
+}
+
+impl Default for NoiseRecipe {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl NoiseRecipe {
+    /// Create a single-octave recipe for `key` with the default scale and
+    /// a range of 0.0 to 1.0.
+    #[must_use]
+    pub fn new(key: u32) -> Self {
+        Self {
+            key,
+            scale: DEFAULT_NOISE_SCALE,
+            octaves: 1,
+            persistence: 0.5,
+            lacunarity: 2.0,
+            range: (0.0, 1.0),
+        }
+    }
+
+    /// Set the key the noise is derived with.
+    #[must_use]
+    pub fn with_key(mut self, key: u32) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Set the input scale (frequency).
+    #[must_use]
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set the fractal octaves, persistence and lacunarity.
+    ///
+    /// Octaves are clamped to 1 to 32, also for recipes loaded from RON or
+    /// edited through reflection.
+    #[must_use]
+    pub fn with_fractal(mut self, octaves: u32, persistence: f64, lacunarity: f64) -> Self {
+        self.octaves = octaves.clamp(1, MAX_OCTAVES);
+        self.persistence = persistence;
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    /// Set the output range.
+    #[must_use]
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.range = (min, max);
+        self
+    }
+
+    /// Get the key.
+    #[must_use]
+    pub fn key(&self) -> u32 {
+        self.key
+    }
+
+    /// Get the input scale.
+    #[must_use]
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Get the fractal octaves, persistence and lacunarity.
+    #[must_use]
+    pub fn fractal(&self) -> (u32, f64, f64) {
+        (self.octaves(), self.persistence, self.lacunarity)
+    }
+
+    /// Get the output range.
+    #[must_use]
+    pub fn range(&self) -> (f64, f64) {
+        self.range
+    }
+
+    /// Map `value` relative to the range, so the minimum becomes 0.0 and
+    /// the maximum 1.0. An empty range maps values by their distance from
+    /// its bound.
+    #[must_use]
+    pub fn normalize(&self, value: f64) -> f64 {
+        let (min, max) = self.range;
+        let span = if max == min { 1.0 } else { max - min };
+        (value - min) / span
+    }
+
+    /// Create the configured noise from `source`.
+    #[must_use]
+    pub fn build(&self, source: &NoiseSource) -> Noise {
+        source
+            .create(self.key)
+            .with_scale(self.scale)
+            .with_range(self.range.0, self.range.1)
+    }
+
+    /// Sample `noise` built by [`Self::build`] with the recipe's octaves.
+    #[must_use]
+    pub fn sample(&self, noise: &Noise, x: f64, y: f64) -> f64 {
+        noise.get_fractal_scaled(x, y, self.octaves(), self.persistence, self.lacunarity)
+    }
+
+    /// Octaves clamped to a usable count.
+    fn octaves(&self) -> u32 {
+        self.octaves.clamp(1, MAX_OCTAVES)
+    }
+
+    /// Rust code recreating this recipe as [`Self::build`] does with
+    /// `source`.
+    ///
+    /// The code pins the output version of `source` when it is not
+    /// [`NOISE_OUTPUT_VERSION`]. Non-finite parameters are written as
+    /// `f64` constants, so the code always compiles.
+    #[must_use]
+    pub fn to_code(&self, source: &NoiseSource) -> String {
+        let version = if source.output_version() == NOISE_OUTPUT_VERSION {
+            String::new()
+        } else {
+            format!("\n    .with_output_version({})", source.output_version())
+        };
+        format!(
+            "let noise = noise_source\n    .create(0x{:04X}_{:04X}){version}\n    .with_scale({})\n    \
+             .with_range({}, {});\nlet value = noise.get_fractal_scaled(x, y, {}, {}, {});\n",
+            self.key >> 16,
+            self.key & 0xFFFF,
+            float_literal(self.scale),
+            float_literal(self.range.0),
+            float_literal(self.range.1),
+            self.octaves(),
+            float_literal(self.persistence),
+            float_literal(self.lacunarity),
+        )
+    }
+
+    /// This recipe as pretty-printed RON.
+    #[must_use]
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("Recipes only contain numbers")
+    }
+
+    /// Parse a recipe from RON. Missing fields use their defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `ron` is not a valid recipe.
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+}
+
+/// `value` as a Rust `f64` expression.
+fn float_literal(value: f64) -> String {
+    if value.is_nan() {
+        "f64::NAN".to_owned()
+    } else if value == f64::INFINITY {
+        "f64::INFINITY".to_owned()
+    } else if value == f64::NEG_INFINITY {
+        "f64::NEG_INFINITY".to_owned()
+    } else {
+        format!("{value:?}")
+    }
+}
+
+/// A rendered [`NoisePreview`].
+#[derive(Clone, Debug)]
+pub struct PreviewFrame {
+    /// The noise image.
+    pub image: Image,
+    /// Pixel counts per histogram bin, from the low to the high end of the
+    /// recipe's range.
+    pub histogram: Vec<u32>,
+    /// The histogram as a bar chart image.
+    pub histogram_image: Image,
+}
+
+/// Renders a [`NoiseRecipe`] into an image for tuning.
+///
+/// With [`NoisePreviewPlugin`], the image and the optional histogram image
+/// are regenerated from the app's [`NoiseSource`] whenever the component
+/// changes, so parameters can be tweaked live through reflection.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use msg_noise::{NoisePreview, NoiseRecipe};
+///
+/// fn spawn_preview(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+///     let image = images.add(Image::default());
+///     let histogram = images.add(Image::default());
+///     let recipe = NoiseRecipe::new(0x5445_5252).with_fractal(4, 0.5, 2.0);
+///
+///     // Show `image` and `histogram` on an `ImageNode` or `Sprite`
+///     commands.spawn(
+///         NoisePreview::new(recipe, image)
+///             .with_extent(512.0)
+///             .with_histogram_image(histogram),
+///     );
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct NoisePreview {
+    recipe: NoiseRecipe,
+    origin: DVec2,
+    extent: f64,
+    resolution: u32,
+    histogram_bins: u32,
+    image: Handle<Image>,
+    histogram_image: Option<Handle<Image>>,
+    #[reflect(ignore)]
+    ramp: Option<ColorRamp>,
+    #[reflect(ignore)]
+    histogram: Vec<u32>,
+}
+
+impl NoisePreview {
+    /// Preview `recipe` into `image`, covering 256 by 256 input units
+    /// from the origin at 256 by 256 pixels.
+    #[must_use]
+    pub fn new(recipe: NoiseRecipe, image: Handle<Image>) -> Self {
+        Self {
+            recipe,
+            origin: DVec2::ZERO,
+            extent: DEFAULT_EXTENT,
+            resolution: 256,
+            histogram_bins: 32,
+            image,
+            histogram_image: None,
+            ramp: None,
+            histogram: Vec::new(),
+        }
+    }
+
+    /// Set the corner of the previewed region.
+    #[must_use]
+    pub fn with_origin(mut self, origin: DVec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Set the width and height of the previewed region in input units.
+    ///
+    /// Non-finite and non-positive extents render the default 256 units.
+    #[must_use]
+    pub fn with_extent(mut self, extent: f64) -> Self {
+        self.extent = extent;
+        self
+    }
+
+    /// Set the image width and height in pixels, from 1 to 4096.
+    #[must_use]
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Set the number of histogram bins, from 1 to 1024. Defaults to 32.
+    #[must_use]
+    pub fn with_histogram_bins(mut self, bins: u32) -> Self {
+        self.histogram_bins = bins;
+        self
+    }
+
+    /// Also render the histogram as a bar chart into `image`.
+    #[must_use]
+    pub fn with_histogram_image(mut self, image: Handle<Image>) -> Self {
+        self.histogram_image = Some(image);
+        self
+    }
+
+    /// Color the preview with `ramp` instead of grayscale.
+    #[must_use]
+    pub fn with_ramp(mut self, ramp: ColorRamp) -> Self {
+        self.ramp = Some(ramp);
+        self
+    }
+
+    /// Get the previewed recipe.
+    #[must_use]
+    pub fn recipe(&self) -> &NoiseRecipe {
+        &self.recipe
+    }
+
+    /// Get the previewed recipe mutably.
+    pub fn recipe_mut(&mut self) -> &mut NoiseRecipe {
+        &mut self.recipe
+    }
+
+    /// Get the preview image handle.
+    #[must_use]
+    pub fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+
+    /// Get the histogram of the last render by [`NoisePreviewPlugin`].
+    #[must_use]
+    pub fn histogram(&self) -> &[u32] {
+        &self.histogram
+    }
+
+    /// Render the preview with noise from `source`.
+    ///
+    /// Values are shown relative to the recipe's range, so the darkest
+    /// pixels are at its minimum and the brightest at its maximum. The
+    /// resolution and histogram bins are clamped to their supported
+    /// ranges and invalid extents replaced by the default, as they may
+    /// have been set through reflection.
+    #[must_use]
+    pub fn render(&self, source: &NoiseSource) -> PreviewFrame {
+        let resolution = self.resolution.clamp(1, MAX_RESOLUTION);
+        let bins = self.histogram_bins.clamp(1, MAX_HISTOGRAM_BINS);
+        let extent = if self.extent.is_finite() && self.extent > 0.0 {
+            self.extent
+        } else {
+            DEFAULT_EXTENT
+        };
+        let noise = self.recipe.build(source);
+        let mut settings = NoiseImage::new(
+            UVec2::splat(resolution),
+            SampleRegion::new(self.origin, DVec2::splat(extent)),
+            NoiseImageFormat::Rgba8,
+        );
+        if let Some(ramp) = &self.ramp {
+            settings = settings.with_ramp(ramp.clone());
+        }
+        let values = crate::timed(|| {
+            settings.sample_fn(|x, y| self.recipe.normalize(self.recipe.sample(&noise, x, y)))
+        });
+
+        let mut histogram = vec![0_u32; bins as usize];
+        for &value in &values {
+            let bin = (value.clamp(0.0, 1.0) * bins as f32) as usize;
+            histogram[bin.min(bins as usize - 1)] += 1;
+        }
+
+        PreviewFrame {
+            image: settings.build_values(values),
+            histogram_image: histogram_image(&histogram),
+            histogram,
+        }
+    }
+}
+
+/// Bar chart of `histogram`, one pixel column per bin, scaled to the
+/// largest bin.
+fn histogram_image(histogram: &[u32]) -> Image {
+    let size = UVec2::new(histogram.len() as u32, HISTOGRAM_HEIGHT);
+    let peak = f64::from(histogram.iter().copied().max().unwrap_or(0).max(1));
+    let settings = NoiseImage::new(
+        size,
+        SampleRegion::new(DVec2::ZERO, size.as_dvec2()),
+        NoiseImageFormat::R8,
+    );
+    settings.build_with(|x, y| {
+        let bar = f64::from(histogram[x as usize]) / peak * f64::from(HISTOGRAM_HEIGHT);
+        // Rows count from the top, bars grow from the bottom
+        if f64::from(HISTOGRAM_HEIGHT) - y < bar {
+            1.0
+        } else {
+            0.0
+        }
+    })
+}
+
+/// Renders [`NoisePreview`] components into their images whenever they
+/// change.
+///
+/// Requires the [`NoiseSource`] resource from `NoisePlugin` and image
+/// assets.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use bevy::prelude::*;
+/// use msg_noise::{NoisePlugin, NoisePreviewPlugin};
+///
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins((NoisePlugin::seeded(12345), NoisePreviewPlugin))
+///     .run();
+/// ```
+#[derive(Debug, Default)]
+pub struct NoisePreviewPlugin;
+
+impl Plugin for NoisePreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<NoisePreview>()
+            .register_type::<NoiseRecipe>()
+            .add_systems(
+                Update,
+                update_noise_previews
+                    .run_if(resource_exists::<NoiseSource>.and(resource_exists::<Assets<Image>>)),
+            );
+    }
+}
+
+/// Re-render previews whose settings changed.
+fn update_noise_previews(
+    source: Res<NoiseSource>,
+    mut images: ResMut<Assets<Image>>,
+    mut previews: Query<&mut NoisePreview, Changed<NoisePreview>>,
+) {
+    for mut preview in &mut previews {
+        let frame = preview.render(&source);
+        if let Some(image) = images.get_mut(&preview.image) {
+            *image = frame.image;
+        }
+        if let Some(handle) = &preview.histogram_image
+            && let Some(image) = images.get_mut(handle)
+        {
+            *image = frame.histogram_image;
+        }
+        preview.bypass_change_detection().histogram = frame.histogram;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_is_deterministic_and_counts_every_pixel() {
+        let source = NoiseSource::new(3);
+        let preview = NoisePreview::new(NoiseRecipe::new(1).with_scale(0.05), Handle::default())
+            .with_resolution(32)
+            .with_extent(64.0)
+            .with_histogram_bins(8);
+
+        let a = preview.render(&source);
+        let b = preview.render(&source);
+        assert_eq!(a.image.data, b.image.data);
+        assert_eq!(a.histogram, b.histogram);
+        assert_eq!(a.histogram.len(), 8);
+        assert_eq!(a.histogram.iter().sum::<u32>(), 32 * 32);
+        assert_eq!(a.histogram_image.width(), 8);
+    }
+
+    #[test]
+    fn out_of_range_settings_are_clamped() {
+        let recipe = NoiseRecipe::from_ron("(key: 1, octaves: 0)").unwrap();
+        assert_eq!(recipe.fractal().0, 1);
+        assert_eq!(
+            NoiseRecipe::from_ron("(octaves: 4000000000)")
+                .unwrap()
+                .fractal()
+                .0,
+            MAX_OCTAVES
+        );
+
+        let noise = recipe.build(&NoiseSource::new(3));
+        assert!(recipe.sample(&noise, 1.5, 2.5).is_finite());
+
+        let mut preview = NoisePreview::new(recipe, Handle::default()).with_resolution(0);
+        preview.histogram_bins = u32::MAX;
+        let frame = preview.render(&NoiseSource::new(3));
+        assert_eq!(frame.image.width(), 1);
+        assert_eq!(frame.histogram.len(), MAX_HISTOGRAM_BINS as usize);
+        assert_eq!(frame.histogram.iter().sum::<u32>(), 1);
+
+        let source = NoiseSource::new(3);
+        let preview = NoisePreview::new(NoiseRecipe::new(1), Handle::default()).with_resolution(8);
+        let expected = preview.render(&source).image.data;
+        for extent in [f64::NAN, f64::INFINITY, -64.0, 0.0] {
+            let frame = preview.clone().with_extent(extent).render(&source);
+            assert_eq!(frame.image.data, expected, "extent {extent}");
+        }
+    }
+
+    #[test]
+    fn recipe_exports_round_trip() {
+        let recipe = NoiseRecipe::new(0xABCD_0123)
+            .with_scale(0.25)
+            .with_fractal(3, 0.6, 1.9)
+            .with_range(-5.0, 5.0);
+        assert_eq!(NoiseRecipe::from_ron(&recipe.to_ron()).unwrap(), recipe);
+        assert_eq!(
+            NoiseRecipe::from_ron("(key: 7)").unwrap(),
+            NoiseRecipe::new(7)
+        );
+
+        let code = recipe.to_code(&NoiseSource::new(1));
+        assert!(code.contains(".create(0xABCD_0123)\n    .with_scale"));
+        assert!(code.contains(".with_scale(0.25)"));
+        assert!(code.contains(".with_range(-5.0, 5.0)"));
+        assert!(code.contains("get_fractal_scaled(x, y, 3, 0.6, 1.9)"));
+
+        let code = NoiseRecipe::new(1)
+            .with_scale(f64::NAN)
+            .with_range(f64::NEG_INFINITY, f64::INFINITY)
+            .to_code(&NoiseSource::new(1));
+        assert!(code.contains(".with_scale(f64::NAN)"));
+        assert!(code.contains(".with_range(f64::NEG_INFINITY, f64::INFINITY)"));
+    }
+
+    #[test]
+    fn histogram_image_bars_scale_to_peak() {
+        let image = histogram_image(&[0, 2, 4]);
+        let column = |x: usize| {
+            (0..HISTOGRAM_HEIGHT as usize)
+                .filter(|y| image.data.as_ref().unwrap()[y * 3 + x] > 0)
+                .count()
+        };
+        assert_eq!(column(0), 0);
+        assert_eq!(column(1), HISTOGRAM_HEIGHT as usize / 2);
+        assert_eq!(column(2), HISTOGRAM_HEIGHT as usize);
+    }
+}
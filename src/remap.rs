@@ -0,0 +1,122 @@
+//! Output remapping curves for [`Noise`](crate::Noise).
+//!
+//! A [`RemapCurve`] reshapes a normalized noise value before it is mapped
+//! into the configured output range.
+
+use crate::float;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A curve applied to normalized noise values (0.0 to 1.0).
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{Noise, RemapCurve};
+///
+/// // Push values towards the extremes for sharper terrain features
+/// let noise = Noise::new(12345)
+///     .with_range(0.0, 100.0)
+///     .with_curve(RemapCurve::Smoothstep);
+///
+/// let height = noise.get_scaled(10.0, 20.0);
+/// ```
+#[derive(Clone, Default)]
+pub enum RemapCurve {
+    /// Leave values unchanged.
+    #[default]
+    Linear,
+    /// Hermite smoothstep, `3t² - 2t³`.
+    Smoothstep,
+    /// Raise values to a power. Exponents above 1.0 favor low values,
+    /// exponents below 1.0 favor high values.
+    Power(f64),
+    /// Piecewise-linear spline through `(input, output)` control points.
+    ///
+    /// Points must be sorted by input. Inputs outside the first and last
+    /// point are held at the nearest point's output.
+    Spline(Vec<(f64, f64)>),
+    /// An arbitrary user-provided function.
+    Custom(Arc<dyn Fn(f64) -> f64 + Send + Sync>),
+}
+
+impl RemapCurve {
+    /// Create a curve from an arbitrary function.
+    #[must_use]
+    pub fn custom(f: impl Fn(f64) -> f64 + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(f))
+    }
+
+    /// Apply the curve to a normalized value.
+    #[must_use]
+    pub fn apply(&self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::Smoothstep => t * t * (3.0 - 2.0 * t),
+            Self::Power(exponent) => t.signum() * float::powf(t.abs(), *exponent),
+            Self::Spline(points) => evaluate_spline(points, t),
+            Self::Custom(f) => f(t),
+        }
+    }
+}
+
+impl fmt::Debug for RemapCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::Linear => f.write_str("Linear"),
+            Self::Smoothstep => f.write_str("Smoothstep"),
+            Self::Power(exponent) => f.debug_tuple("Power").field(exponent).finish(),
+            Self::Spline(points) => f.debug_tuple("Spline").field(points).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Evaluate a piecewise-linear spline at `t`.
+fn evaluate_spline(points: &[(f64, f64)], t: f64) -> f64 {
+    let (Some(&(first_x, first_y)), Some(&(last_x, last_y))) = (points.first(), points.last())
+    else {
+        return t;
+    };
+
+    if t <= first_x {
+        return first_y;
+    }
+    if t >= last_x {
+        return last_y;
+    }
+
+    let upper = points.partition_point(|&(x, _)| x <= t);
+    let (x0, y0) = points[upper - 1];
+    let (x1, y1) = points[upper];
+    let span = x1 - x0;
+    if span <= 0.0 {
+        return y1;
+    }
+    y0 + (t - x0) / span * (y1 - y0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn smoothstep_keeps_endpoints() {
+        let curve = RemapCurve::Smoothstep;
+        assert!((curve.apply(0.0)).abs() < f64::EPSILON);
+        assert!((curve.apply(0.5) - 0.5).abs() < f64::EPSILON);
+        assert!((curve.apply(1.0) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn spline_interpolates_and_holds_ends() {
+        let curve = RemapCurve::Spline(vec![(0.2, 0.0), (0.6, 1.0), (1.0, 0.5)]);
+
+        assert!((curve.apply(0.0)).abs() < f64::EPSILON);
+        assert!((curve.apply(0.4) - 0.5).abs() < 1e-12);
+        assert!((curve.apply(0.8) - 0.75).abs() < 1e-12);
+        assert!((curve.apply(2.0) - 0.5).abs() < f64::EPSILON);
+    }
+}
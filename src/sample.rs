@@ -0,0 +1,146 @@
+//! Runtime selection of [`Noise`] samplers.
+//!
+//! Tools that sample noise generically (statistics, image generation)
+//! take a [`SampleMode`] instead of hard-coding a sampler method, and a
+//! [`SampleRegion`] describing which part of the noise field to cover.
+
+use crate::Noise;
+use glam::{DVec2, UVec2};
+
+/// A rectangular region of input coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SampleRegion {
+    /// Minimum corner.
+    pub origin: DVec2,
+    /// Width and height.
+    pub size: DVec2,
+}
+
+impl SampleRegion {
+    /// Create a region from its minimum corner and size.
+    #[must_use]
+    pub fn new(origin: DVec2, size: DVec2) -> Self {
+        Self { origin, size }
+    }
+
+    /// Create a region spanning two corners.
+    #[must_use]
+    pub fn from_corners(a: DVec2, b: DVec2) -> Self {
+        let origin = a.min(b);
+        Self {
+            origin,
+            size: a.max(b) - origin,
+        }
+    }
+
+    /// Position of the center of cell `cell` when the region is divided
+    /// into a grid of `resolution` cells.
+    #[must_use]
+    pub fn cell_center(&self, cell: UVec2, resolution: UVec2) -> DVec2 {
+        self.origin + (cell.as_dvec2() + 0.5) * self.size / resolution.as_dvec2()
+    }
+}
+
+/// Which [`Noise`] sampler to evaluate.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{Noise, SampleMode};
+///
+/// let noise = Noise::new(12345).with_range(0.0, 255.0);
+///
+/// let a = noise.sample(SampleMode::Scaled, 10.0, 20.0);
+/// let b = noise.get_scaled(10.0, 20.0);
+/// assert_eq!(a, b);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(bevy::reflect::Reflect))]
+pub enum SampleMode {
+    /// [`Noise::get_raw`] / [`Noise::get_raw_3d`].
+    Raw,
+    /// [`Noise::get_normalized`] / [`Noise::get_normalized_3d`].
+    #[default]
+    Normalized,
+    /// [`Noise::get_absolute`].
+    Absolute,
+    /// [`Noise::get_scaled`] / [`Noise::get_scaled_3d`].
+    Scaled,
+    /// [`Noise::get_absolute_scaled`].
+    AbsoluteScaled,
+    /// [`Noise::get_calibrated`] / [`Noise::get_calibrated_3d`].
+    Calibrated,
+    /// [`Noise::get_equalized`] / [`Noise::get_equalized_3d`].
+    Equalized,
+    /// [`Noise::get_fractal`] / [`Noise::get_fractal_3d`].
+    Fractal {
+        /// Number of octaves.
+        octaves: u32,
+        /// Amplitude multiplier per octave.
+        persistence: f64,
+        /// Frequency multiplier per octave.
+        lacunarity: f64,
+    },
+    /// [`Noise::get_fractal_scaled`].
+    FractalScaled {
+        /// Number of octaves.
+        octaves: u32,
+        /// Amplitude multiplier per octave.
+        persistence: f64,
+        /// Frequency multiplier per octave.
+        lacunarity: f64,
+    },
+}
+
+impl Noise {
+    /// Evaluate the 2D sampler selected by `mode`.
+    #[must_use]
+    pub fn sample(&self, mode: SampleMode, x: f64, y: f64) -> f64 {
+        match mode {
+            SampleMode::Raw => self.get_raw(x, y),
+            SampleMode::Normalized => self.get_normalized(x, y),
+            SampleMode::Absolute => self.get_absolute(x, y),
+            SampleMode::Scaled => self.get_scaled(x, y),
+            SampleMode::AbsoluteScaled => self.get_absolute_scaled(x, y),
+            SampleMode::Calibrated => self.get_calibrated(x, y),
+            SampleMode::Equalized => self.get_equalized(x, y),
+            SampleMode::Fractal {
+                octaves,
+                persistence,
+                lacunarity,
+            } => self.get_fractal(x, y, octaves, persistence, lacunarity),
+            SampleMode::FractalScaled {
+                octaves,
+                persistence,
+                lacunarity,
+            } => self.get_fractal_scaled(x, y, octaves, persistence, lacunarity),
+        }
+    }
+
+    /// Evaluate the 3D equivalent of the sampler selected by `mode`.
+    #[must_use]
+    pub fn sample_3d(&self, mode: SampleMode, x: f64, y: f64, z: f64) -> f64 {
+        match mode {
+            SampleMode::Raw => self.get_raw_3d(x, y, z),
+            SampleMode::Normalized => self.get_normalized_3d(x, y, z),
+            SampleMode::Absolute => self.get_raw_3d(x, y, z).abs(),
+            SampleMode::Scaled => self.get_scaled_3d(x, y, z),
+            SampleMode::AbsoluteScaled => self.map_to_range(self.get_raw_3d(x, y, z).abs()),
+            SampleMode::Calibrated => self.get_calibrated_3d(x, y, z),
+            SampleMode::Equalized => self.get_equalized_3d(x, y, z),
+            SampleMode::Fractal {
+                octaves,
+                persistence,
+                lacunarity,
+            } => self.get_fractal_3d(x, y, z, octaves, persistence, lacunarity),
+            SampleMode::FractalScaled {
+                octaves,
+                persistence,
+                lacunarity,
+            } => {
+                let fractal = self.get_fractal_3d(x, y, z, octaves, persistence, lacunarity);
+                self.map_to_range((fractal + 1.0) * 0.5)
+            }
+        }
+    }
+}
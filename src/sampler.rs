@@ -0,0 +1,160 @@
+//! A common interface for scalar noise samplers.
+//!
+//! [`NoiseSampler`] lets generic code accept any noise in this crate, and
+//! [`Noise`] additionally implements [`noise::NoiseFn`] so configured layers
+//! can be fed into `noise` combinators and third-party code.
+
+use crate::Noise;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use noise::NoiseFn;
+
+/// Scalar noise that can be sampled in 2D and 3D.
+///
+/// Values are raw noise, typically between -1.0 and 1.0, with any input
+/// transform already applied.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::{NoiseSampler, NoiseSource};
+///
+/// fn average(sampler: &impl NoiseSampler) -> f64 {
+///     (0..100).map(|i| sampler.sample_raw(f64::from(i), 0.0)).sum::<f64>() / 100.0
+/// }
+///
+/// let source = NoiseSource::new(12345);
+/// let noise = source.create(0x4156_4745).with_scale(0.1);
+/// assert!(average(&noise).abs() < 1.0);
+///
+/// // Samplers can also be used as trait objects
+/// let layers: Vec<Box<dyn NoiseSampler>> = vec![Box::new(noise.clone()), Box::new(noise)];
+/// assert_eq!(layers[0].sample_raw(1.0, 2.0), layers[1].sample_raw(1.0, 2.0));
+/// ```
+pub trait NoiseSampler {
+    /// Sample 2D noise at `(x, y)`.
+    fn sample_raw(&self, x: f64, y: f64) -> f64;
+
+    /// Sample 3D noise at `(x, y, z)`.
+    fn sample_raw_3d(&self, x: f64, y: f64, z: f64) -> f64;
+}
+
+impl NoiseSampler for Noise {
+    #[inline]
+    fn sample_raw(&self, x: f64, y: f64) -> f64 {
+        self.get_raw(x, y)
+    }
+
+    #[inline]
+    fn sample_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.get_raw_3d(x, y, z)
+    }
+}
+
+impl<T: NoiseSampler + ?Sized> NoiseSampler for &T {
+    #[inline]
+    fn sample_raw(&self, x: f64, y: f64) -> f64 {
+        (**self).sample_raw(x, y)
+    }
+
+    #[inline]
+    fn sample_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        (**self).sample_raw_3d(x, y, z)
+    }
+}
+
+impl<T: NoiseSampler + ?Sized> NoiseSampler for Box<T> {
+    #[inline]
+    fn sample_raw(&self, x: f64, y: f64) -> f64 {
+        (**self).sample_raw(x, y)
+    }
+
+    #[inline]
+    fn sample_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        (**self).sample_raw_3d(x, y, z)
+    }
+}
+
+impl<T: NoiseSampler + ?Sized> NoiseSampler for Arc<T> {
+    #[inline]
+    fn sample_raw(&self, x: f64, y: f64) -> f64 {
+        (**self).sample_raw(x, y)
+    }
+
+    #[inline]
+    fn sample_raw_3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        (**self).sample_raw_3d(x, y, z)
+    }
+}
+
+/// Raw 2D noise, honoring the input transform.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::Noise;
+/// use noise::{Add, NoiseFn};
+///
+/// let a = Noise::new(1).with_scale(0.1);
+/// let b = Noise::new(2).with_scale(0.05);
+/// let sum = Add::new(a.clone(), b.clone());
+/// assert_eq!(sum.get([3.0, 4.0]), a.get_raw(3.0, 4.0) + b.get_raw(3.0, 4.0));
+/// ```
+impl NoiseFn<f64, 2> for Noise {
+    #[inline]
+    fn get(&self, point: [f64; 2]) -> f64 {
+        self.get_raw(point[0], point[1])
+    }
+}
+
+/// Raw 3D noise, honoring the input transform.
+impl NoiseFn<f64, 3> for Noise {
+    #[inline]
+    fn get(&self, point: [f64; 3]) -> f64 {
+        self.get_raw_3d(point[0], point[1], point[2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::DVec3;
+    use noise::{Multiply, ScaleBias};
+
+    #[test]
+    fn noise_fn_matches_raw_samplers() {
+        let noise = Noise::new(9)
+            .with_axis_scale(DVec3::new(0.1, 0.2, 0.3))
+            .with_axis_offset(DVec3::new(5.0, -3.0, 1.0));
+        for i in 0..20 {
+            let t = f64::from(i) * 1.7;
+            assert_eq!(NoiseFn::get(&noise, [t, -t]), noise.get_raw(t, -t));
+            assert_eq!(
+                NoiseFn::get(&noise, [t, -t, t * 0.5]),
+                noise.get_raw_3d(t, -t, t * 0.5)
+            );
+        }
+    }
+
+    #[test]
+    fn noise_works_in_combinators() {
+        let noise = Noise::new(4).with_scale(0.1);
+        let biased = ScaleBias::new(noise.clone()).set_scale(2.0).set_bias(1.0);
+        let squared = Multiply::new(noise.clone(), noise.clone());
+        let value = noise.get_raw(1.5, 2.5);
+        assert!((NoiseFn::<f64, 2>::get(&biased, [1.5, 2.5]) - (value * 2.0 + 1.0)).abs() < 1e-12);
+        assert_eq!(NoiseFn::<f64, 2>::get(&squared, [1.5, 2.5]), value * value);
+    }
+
+    #[test]
+    fn wrappers_forward_to_inner_sampler() {
+        let noise = Noise::new(2).with_scale(0.1);
+        let boxed: Box<dyn NoiseSampler> = Box::new(noise.clone());
+        let shared: Arc<dyn NoiseSampler> = Arc::new(noise.clone());
+        let expected = noise.sample_raw_3d(1.0, 2.0, 3.0);
+        assert_eq!(boxed.sample_raw_3d(1.0, 2.0, 3.0), expected);
+        assert_eq!(shared.sample_raw_3d(1.0, 2.0, 3.0), expected);
+        let borrowed: &dyn NoiseSampler = &noise;
+        assert_eq!(borrowed.sample_raw(4.0, 5.0), noise.get_raw(4.0, 5.0));
+    }
+}
@@ -0,0 +1,548 @@
+//! Deterministic point scattering for object placement.
+//!
+//! [`Scatter`] generates well-spaced points for trees, rocks and spawn
+//! locations. Points are generated per fixed world-space tile from a seed
+//! derived with [`NoiseSource::create_scatter`](crate::NoiseSource::create_scatter),
+//! so a chunk always receives the same points no matter which chunks were
+//! generated before it, and neighboring chunks agree on their shared border.
+
+use crate::{Noise, SampleMode, SampleRegion};
+use glam::{DVec2, IVec2};
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::fmt;
+use std::sync::Arc;
+
+/// Bridson candidate attempts per active point.
+const DEFAULT_ATTEMPTS: u32 = 30;
+
+/// Default tile size in multiples of the largest spacing radius.
+const TILE_RADII: f64 = 8.0;
+
+/// Maximum number of background grid cells per tile axis.
+const MAX_GRID_CELLS: f64 = 256.0;
+
+/// How points are distributed.
+#[derive(Clone)]
+pub enum ScatterMethod {
+    /// Bridson Poisson disk sampling: no two points closer than `radius`.
+    Poisson {
+        /// Minimum distance between points.
+        radius: f64,
+    },
+    /// One point per grid cell, displaced randomly within the cell.
+    JitteredGrid {
+        /// Grid cell size.
+        spacing: f64,
+        /// Displacement as a fraction of the cell size, from 0.0 (regular
+        /// grid) to 1.0 (anywhere in the cell).
+        jitter: f64,
+    },
+    /// Poisson disk sampling where a density function in 0.0..=1.0 controls
+    /// the local spacing: density 1.0 spaces points `min_radius` apart,
+    /// density 0.0 spaces them `max_radius` apart.
+    DensityPoisson {
+        /// Spacing at full density.
+        min_radius: f64,
+        /// Spacing at zero density.
+        max_radius: f64,
+        /// Density as a function of world x and y.
+        density: Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>,
+    },
+}
+
+impl ScatterMethod {
+    /// Density-modulated Poisson sampling controlled by `noise`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either radius is not positive and finite.
+    #[must_use]
+    pub fn density_noise(
+        noise: &Noise,
+        mode: SampleMode,
+        min_radius: f64,
+        max_radius: f64,
+    ) -> Self {
+        let noise = noise.clone();
+        let method = Self::DensityPoisson {
+            min_radius,
+            max_radius,
+            density: Arc::new(move |x, y| noise.sample(mode, x, y)),
+        };
+        method.validate();
+        method
+    }
+
+    /// Panic unless every radius or spacing is positive and finite, which
+    /// would otherwise make sampling loop forever, and grid jitter is
+    /// within 0.0..=1.0, which keeps each point inside its own cell.
+    fn validate(&self) {
+        let valid = |value: f64| value.is_finite() && value > 0.0;
+        let ok = match self {
+            Self::Poisson { radius } => valid(*radius),
+            Self::JitteredGrid { spacing, jitter } => {
+                valid(*spacing) && (0.0..=1.0).contains(jitter)
+            }
+            Self::DensityPoisson {
+                min_radius,
+                max_radius,
+                ..
+            } => valid(*min_radius) && valid(*max_radius),
+        };
+        assert!(ok, "invalid scatter spacing or jitter: {self:?}");
+    }
+
+    /// Smallest and largest spacing radius.
+    fn radii(&self) -> (f64, f64) {
+        match self {
+            Self::Poisson { radius } => (*radius, *radius),
+            Self::JitteredGrid { spacing, .. } => (*spacing, *spacing),
+            Self::DensityPoisson {
+                min_radius,
+                max_radius,
+                ..
+            } => (min_radius.min(*max_radius), min_radius.max(*max_radius)),
+        }
+    }
+
+    /// Spacing radius at `point`.
+    fn radius_at(&self, point: DVec2) -> f64 {
+        match self {
+            Self::Poisson { radius } => *radius,
+            Self::JitteredGrid { spacing, .. } => *spacing,
+            Self::DensityPoisson {
+                min_radius,
+                max_radius,
+                density,
+            } => {
+                let density = density(point.x, point.y).clamp(0.0, 1.0);
+                max_radius + (min_radius - max_radius) * density
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ScatterMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::Poisson { radius } => f.debug_struct("Poisson").field("radius", radius).finish(),
+            Self::JitteredGrid { spacing, jitter } => f
+                .debug_struct("JitteredGrid")
+                .field("spacing", spacing)
+                .field("jitter", jitter)
+                .finish(),
+            Self::DensityPoisson {
+                min_radius,
+                max_radius,
+                ..
+            } => f
+                .debug_struct("DensityPoisson")
+                .field("min_radius", min_radius)
+                .field("max_radius", max_radius)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Deterministic, chunk-stable point scatterer.
+///
+/// Poisson methods run Bridson's algorithm independently in each world
+/// tile. Where points of neighboring tiles are too close, the point from
+/// the tile with the lower hashed priority is dropped, which only depends
+/// on the two tiles involved and leaves small gaps along tile borders.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::math::DVec2;
+/// use msg_noise::{NoiseSource, SampleRegion, ScatterMethod};
+///
+/// let source = NoiseSource::new(12345);
+/// let trees = source.create_scatter(0x5452_4545, ScatterMethod::Poisson { radius: 4.0 });
+///
+/// let chunk = SampleRegion::new(DVec2::new(64.0, 0.0), DVec2::splat(64.0));
+/// for point in trees.points(chunk) {
+///     // spawn a tree at (point.x, point.y)
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Scatter {
+    seed: u32,
+    method: ScatterMethod,
+    attempts: u32,
+    tile_size: Option<f64>,
+}
+
+impl Scatter {
+    /// Create a scatterer with the given seed.
+    ///
+    /// Prefer [`NoiseSource::create_scatter`](crate::NoiseSource::create_scatter)
+    /// to derive the seed from the global noise seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a radius or the grid spacing of `method` is not positive
+    /// and finite, or the grid jitter is not within 0.0..=1.0.
+    #[must_use]
+    pub fn new(seed: u32, method: ScatterMethod) -> Self {
+        method.validate();
+        Self {
+            seed,
+            method,
+            attempts: DEFAULT_ATTEMPTS,
+            tile_size: None,
+        }
+    }
+
+    /// Set the number of Bridson candidate attempts per active point.
+    /// Higher values pack points more tightly. Defaults to 30.
+    #[must_use]
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Set the size of the world tiles Poisson points are generated in.
+    ///
+    /// Defaults to eight times the largest spacing radius and is never
+    /// smaller than twice that radius. Changing it changes the points.
+    #[must_use]
+    pub fn with_tile_size(mut self, tile_size: f64) -> Self {
+        self.tile_size = Some(tile_size);
+        self
+    }
+
+    /// Get the seed.
+    #[must_use]
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Get the scatter method.
+    #[must_use]
+    pub fn method(&self) -> &ScatterMethod {
+        &self.method
+    }
+
+    /// Points inside `region`, including its minimum edges and excluding
+    /// its maximum edges, so adjacent regions never share a point.
+    #[must_use]
+    pub fn points(&self, region: SampleRegion) -> Vec<DVec2> {
+        let contains = |p: &DVec2| {
+            let max = region.origin + region.size;
+            p.cmpge(region.origin).all() && p.cmplt(max).all()
+        };
+
+        if let ScatterMethod::JitteredGrid { spacing, jitter } = self.method {
+            let min = (region.origin / spacing).floor().as_ivec2();
+            let max = ((region.origin + region.size) / spacing).ceil().as_ivec2();
+            let mut points = Vec::new();
+            for y in min.y..max.y {
+                for x in min.x..max.x {
+                    let cell = IVec2::new(x, y);
+                    let mut rng = TileRng::new(self.seed, cell);
+                    let offset = DVec2::new(rng.next_f64(), rng.next_f64()) - 0.5;
+                    let point = (cell.as_dvec2() + 0.5 + offset * jitter) * spacing;
+                    if contains(&point) {
+                        points.push(point);
+                    }
+                }
+            }
+            return points;
+        }
+
+        let tile_size = self.tile_size();
+        let min = (region.origin / tile_size).floor().as_ivec2();
+        let max = ((region.origin + region.size) / tile_size)
+            .ceil()
+            .as_ivec2();
+
+        // Tiles in the region plus one ring of neighbors for border checks
+        let mut tiles = HashMap::new();
+        for y in min.y - 1..=max.y {
+            for x in min.x - 1..=max.x {
+                let tile = IVec2::new(x, y);
+                tiles.insert(tile, self.tile_points(tile, tile_size));
+            }
+        }
+
+        let mut points = Vec::new();
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let tile = IVec2::new(x, y);
+                let priority = tile_priority(self.seed, tile);
+                for &(point, radius) in &tiles[&tile] {
+                    if !contains(&point) {
+                        continue;
+                    }
+                    let dropped = NEIGHBORS.iter().any(|offset| {
+                        let neighbor = tile + *offset;
+                        tile_priority(self.seed, neighbor) > priority
+                            && tiles[&neighbor]
+                                .iter()
+                                .any(|(other, r)| point.distance(*other) < radius.max(*r))
+                    });
+                    if !dropped {
+                        points.push(point);
+                    }
+                }
+            }
+        }
+        points
+    }
+
+    fn tile_size(&self) -> f64 {
+        let (_, max_radius) = self.method.radii();
+        let min_size = 2.0 * max_radius;
+        self.tile_size
+            .filter(|size| size.is_finite())
+            .unwrap_or(TILE_RADII * max_radius)
+            .max(min_size)
+    }
+
+    /// Bridson Poisson disk points of a single tile, with their radii.
+    fn tile_points(&self, tile: IVec2, tile_size: f64) -> Vec<(DVec2, f64)> {
+        let (min_radius, max_radius) = self.method.radii();
+        let origin = tile.as_dvec2() * tile_size;
+        let mut rng = TileRng::new(self.seed, tile);
+
+        // Background grid holding one point per cell, unless the radii vary
+        // so much that the cell count is capped
+        let cell_size = (min_radius / std::f64::consts::SQRT_2).max(tile_size / MAX_GRID_CELLS);
+        let cells = (tile_size / cell_size).ceil() as i32;
+        let reach = (max_radius / cell_size).ceil() as i32;
+        let mut grid: Vec<Vec<usize>> = vec![Vec::new(); (cells * cells) as usize];
+        let cell_of = |p: DVec2| {
+            ((p - origin) / cell_size)
+                .floor()
+                .as_ivec2()
+                .clamp(IVec2::ZERO, IVec2::splat(cells - 1))
+        };
+
+        let mut points: Vec<(DVec2, f64)> = Vec::new();
+        let mut active = Vec::new();
+        let first = origin + DVec2::new(rng.next_f64(), rng.next_f64()) * tile_size;
+        let cell = cell_of(first);
+        grid[(cell.x + cell.y * cells) as usize].push(0);
+        points.push((first, self.method.radius_at(first)));
+        active.push(0);
+
+        while !active.is_empty() {
+            let slot = (rng.next_f64() * active.len() as f64) as usize % active.len();
+            let (center, radius) = points[active[slot]];
+            let mut found = false;
+            for _ in 0..self.attempts {
+                let angle = rng.next_f64() * TAU;
+                let distance = radius * (1.0 + rng.next_f64());
+                let candidate = center + DVec2::from_angle(angle) * distance;
+                let local = candidate - origin;
+                if local.cmplt(DVec2::ZERO).any() || local.cmpge(DVec2::splat(tile_size)).any() {
+                    continue;
+                }
+
+                let candidate_radius = self.method.radius_at(candidate);
+                let cell = cell_of(candidate);
+                let lo = (cell - reach).max(IVec2::ZERO);
+                let hi = (cell + reach).min(IVec2::splat(cells - 1));
+                let conflict = (lo.y..=hi.y).any(|y| {
+                    (lo.x..=hi.x).any(|x| {
+                        grid[(x + y * cells) as usize].iter().any(|&i| {
+                            let (other, r) = points[i];
+                            candidate.distance(other) < candidate_radius.max(r)
+                        })
+                    })
+                });
+                if !conflict {
+                    grid[(cell.x + cell.y * cells) as usize].push(points.len());
+                    active.push(points.len());
+                    points.push((candidate, candidate_radius));
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                active.swap_remove(slot);
+            }
+        }
+
+        points
+    }
+}
+
+/// Offsets of the eight neighboring tiles.
+const NEIGHBORS: [IVec2; 8] = [
+    IVec2::new(-1, -1),
+    IVec2::new(0, -1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 0),
+    IVec2::new(1, 0),
+    IVec2::new(-1, 1),
+    IVec2::new(0, 1),
+    IVec2::new(1, 1),
+];
+
+/// Priority of a tile when resolving conflicts along tile borders.
+fn tile_priority(seed: u32, tile: IVec2) -> u64 {
+    // Offset the seed so priorities are independent of tile contents
+    TileRng::new(seed ^ 0x9E37_79B9, tile).next_u64()
+}
+
+/// Small deterministic generator seeded from a seed and grid coordinate.
+pub(crate) struct TileRng(u64);
+
+impl TileRng {
+    pub(crate) fn new(seed: u32, cell: IVec2) -> Self {
+        let packed = (u64::from(cell.x as u32) << 32) | u64::from(cell.y as u32);
+        Self::from_bits(seed, packed)
+    }
+
+    /// Generator seeded from the exact position of a point.
+    pub(crate) fn at_point(seed: u32, point: DVec2) -> Self {
+        Self::from_bits(seed, point.x.to_bits() ^ point.y.to_bits().rotate_left(32))
+    }
+
+    fn from_bits(seed: u32, bits: u64) -> Self {
+        let mut rng = Self(u64::from(seed) ^ bits.rotate_left(17));
+        rng.0 = rng.next_u64();
+        rng
+    }
+
+    /// `SplitMix64` step.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0.0..1.0`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn min_distance(points: &[DVec2]) -> f64 {
+        let mut min = f64::INFINITY;
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                min = min.min(a.distance(*b));
+            }
+        }
+        min
+    }
+
+    #[test]
+    fn poisson_points_respect_radius() {
+        let scatter = Scatter::new(42, ScatterMethod::Poisson { radius: 3.0 });
+        let points = scatter.points(SampleRegion::new(DVec2::splat(-50.0), DVec2::splat(100.0)));
+
+        assert!(points.len() > 300);
+        assert!(min_distance(&points) >= 3.0);
+    }
+
+    #[test]
+    fn invalid_spacing_is_rejected() {
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+
+        let noise = Noise::new(1);
+        let invalid = [
+            ScatterMethod::Poisson { radius: 0.0 },
+            ScatterMethod::Poisson { radius: f64::NAN },
+            ScatterMethod::JitteredGrid {
+                spacing: 0.0,
+                jitter: 0.5,
+            },
+            ScatterMethod::JitteredGrid {
+                spacing: 2.0,
+                jitter: 1.5,
+            },
+            ScatterMethod::JitteredGrid {
+                spacing: 2.0,
+                jitter: f64::NAN,
+            },
+            ScatterMethod::DensityPoisson {
+                min_radius: -1.0,
+                max_radius: 4.0,
+                density: Arc::new(|_, _| 1.0),
+            },
+            ScatterMethod::DensityPoisson {
+                min_radius: 1.0,
+                max_radius: f64::INFINITY,
+                density: Arc::new(|_, _| 1.0),
+            },
+        ];
+        for method in invalid {
+            let result = catch_unwind(AssertUnwindSafe(|| Scatter::new(0, method)));
+            assert!(result.is_err());
+        }
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            ScatterMethod::density_noise(&noise, SampleMode::Normalized, f64::NAN, 4.0)
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn jittered_grid_has_one_point_per_cell() {
+        let scatter = Scatter::new(
+            42,
+            ScatterMethod::JitteredGrid {
+                spacing: 2.0,
+                jitter: 1.0,
+            },
+        );
+        let points = scatter.points(SampleRegion::new(DVec2::ZERO, DVec2::splat(20.0)));
+
+        assert_eq!(points.len(), 100);
+        for point in points {
+            assert!(point.cmpge(DVec2::ZERO).all() && point.cmplt(DVec2::splat(20.0)).all());
+        }
+    }
+
+    #[test]
+    fn jittered_grid_is_stable_across_cell_boundaries() {
+        let scatter = Scatter::new(
+            7,
+            ScatterMethod::JitteredGrid {
+                spacing: 2.0,
+                jitter: 1.0,
+            },
+        );
+        let mut whole = scatter.points(SampleRegion::new(DVec2::ZERO, DVec2::new(20.0, 10.0)));
+        let mut split = scatter.points(SampleRegion::new(DVec2::ZERO, DVec2::new(10.0, 10.0)));
+        split.extend(scatter.points(SampleRegion::new(
+            DVec2::new(10.0, 0.0),
+            DVec2::new(10.0, 10.0),
+        )));
+
+        let key = |p: &DVec2| (p.x.to_bits(), p.y.to_bits());
+        whole.sort_by_key(key);
+        split.sort_by_key(key);
+        assert_eq!(whole.len(), 50);
+        assert_eq!(whole, split);
+    }
+
+    #[test]
+    fn density_controls_spacing() {
+        let method = ScatterMethod::DensityPoisson {
+            min_radius: 1.0,
+            max_radius: 4.0,
+            density: Arc::new(|x, _| if x < 0.0 { 1.0 } else { 0.0 }),
+        };
+        let scatter = Scatter::new(7, method);
+
+        let dense = scatter.points(SampleRegion::new(
+            DVec2::new(-40.0, 0.0),
+            DVec2::splat(32.0),
+        ));
+        let sparse = scatter.points(SampleRegion::new(DVec2::new(8.0, 0.0), DVec2::splat(32.0)));
+
+        assert!(dense.len() > sparse.len() * 8);
+        assert!(min_distance(&sparse) >= 4.0);
+    }
+}
@@ -0,0 +1,338 @@
+//! Time-varying noise signals for animation and camera shake.
+//!
+//! [`NoiseSignal`] samples smooth 1D noise over [`Time`] on six independent
+//! channels. It can be read directly to drive flickering lights or other
+//! values, and it offsets the [`Transform`] of its entity for trauma-style
+//! shake when translation or rotation amplitudes are set.
+
+use crate::Noise;
+#[cfg(feature = "bevy")]
+use bevy::ecs::{entity_disabling::Disabled, query::Allow};
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+use glam::{EulerRot, Quat, Vec3};
+
+/// Distance along the 1D noise line between channels.
+const CHANNEL_SPACING: f64 = 1000.0;
+
+/// Number of independent channels: three for translation, three for
+/// rotation.
+const CHANNELS: usize = 6;
+
+/// A noise channel evaluated over time.
+///
+/// The signal's intensity is `trauma ^ exponent`. Trauma decays linearly
+/// over time, so adding trauma on impact produces a shake that fades out.
+/// Signals that should run forever keep the default trauma of 1.0 and no
+/// decay.
+///
+/// Time is multiplied by the frequency and then passed through the noise's
+/// input transform, so a noise with a scale of 1.0 varies roughly
+/// `frequency` times per second.
+///
+/// As a component next to a `Transform`, the signal is advanced by
+/// `NoisePlugin` in `Update` and its [`offset`](Self::offset) is added to
+/// the transform in `PostUpdate`, before transform propagation. The offset
+/// is removed again in `PreUpdate`, so systems in `Update`, such as a
+/// camera follow system setting the transform absolutely, always see and
+/// write the pose without shake. Removing the component also removes its
+/// offset.
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::math::Vec3;
+/// use msg_noise::{NoiseSignal, NoiseSource};
+///
+/// let source = NoiseSource::new(12345);
+/// let mut shake = NoiseSignal::new(source.create(0x5348_4B45).with_scale(1.0))
+///     .with_frequency(15.0)
+///     .with_translation(Vec3::new(0.3, 0.3, 0.0))
+///     .with_rotation(Vec3::new(0.0, 0.0, 0.05))
+///     .with_trauma(0.0)
+///     .with_decay(1.5);
+///
+/// // On hit
+/// shake.add_trauma(0.5);
+///
+/// // Every frame
+/// shake.advance(1.0 / 60.0);
+/// assert!(shake.trauma() < 0.5);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct NoiseSignal {
+    noise: Noise,
+    frequency: f64,
+    octaves: u32,
+    translation: Vec3,
+    rotation: Vec3,
+    trauma: f32,
+    decay: f32,
+    exponent: f32,
+    elapsed: f64,
+    #[cfg(feature = "bevy")]
+    applied_translation: Vec3,
+    #[cfg(feature = "bevy")]
+    applied_rotation: Quat,
+}
+
+impl NoiseSignal {
+    /// Create a signal sampling `noise`.
+    #[must_use]
+    pub fn new(noise: Noise) -> Self {
+        Self {
+            noise,
+            frequency: 1.0,
+            octaves: 1,
+            translation: Vec3::ZERO,
+            rotation: Vec3::ZERO,
+            trauma: 1.0,
+            decay: 0.0,
+            exponent: 2.0,
+            elapsed: 0.0,
+            #[cfg(feature = "bevy")]
+            applied_translation: Vec3::ZERO,
+            #[cfg(feature = "bevy")]
+            applied_rotation: Quat::IDENTITY,
+        }
+    }
+
+    /// Set the time multiplier applied before sampling.
+    #[must_use]
+    pub fn with_frequency(mut self, frequency: f64) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Set the number of fractal octaves. More octaves add finer jitter.
+    #[must_use]
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves.max(1);
+        self
+    }
+
+    /// Set the maximum translation offset per axis at full intensity.
+    #[must_use]
+    pub fn with_translation(mut self, amplitude: Vec3) -> Self {
+        self.translation = amplitude;
+        self
+    }
+
+    /// Set the maximum rotation offset per axis, in radians, at full
+    /// intensity.
+    #[must_use]
+    pub fn with_rotation(mut self, amplitude: Vec3) -> Self {
+        self.rotation = amplitude;
+        self
+    }
+
+    /// Set the initial trauma (0.0 to 1.0).
+    #[must_use]
+    pub fn with_trauma(mut self, trauma: f32) -> Self {
+        self.trauma = trauma.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set how much trauma is lost per second.
+    #[must_use]
+    pub fn with_decay(mut self, decay: f32) -> Self {
+        self.decay = decay.max(0.0);
+        self
+    }
+
+    /// Set the exponent applied to trauma to get the intensity. Higher
+    /// values make small amounts of trauma subtler. Defaults to 2.0.
+    #[must_use]
+    pub fn with_trauma_exponent(mut self, exponent: f32) -> Self {
+        self.exponent = exponent;
+        self
+    }
+
+    /// Add trauma, saturating at 1.0.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Get the current trauma.
+    #[must_use]
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Get the current intensity, `trauma ^ exponent`.
+    #[must_use]
+    pub fn intensity(&self) -> f32 {
+        self.trauma.powf(self.exponent)
+    }
+
+    /// Get the signal time in seconds.
+    #[must_use]
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Value of channel `channel` (0 to 5) scaled by the intensity,
+    /// between roughly -1.0 and 1.0.
+    ///
+    /// Channels 0 to 2 drive translation and 3 to 5 drive rotation, but
+    /// any channel can be read for other purposes.
+    #[must_use]
+    pub fn channel(&self, channel: usize) -> f64 {
+        let t = self.elapsed * self.frequency + channel as f64 * CHANNEL_SPACING;
+        self.noise.get_fractal_1d(t, self.octaves, 0.5, 2.0) * f64::from(self.intensity())
+    }
+
+    /// Value of channel 0, for signals driving a single value.
+    #[must_use]
+    pub fn value(&self) -> f64 {
+        self.channel(0)
+    }
+
+    /// Advance the signal by `delta` seconds and decay its trauma.
+    pub fn advance(&mut self, delta: f64) {
+        self.elapsed += delta;
+        self.trauma = (self.trauma - self.decay * delta as f32).max(0.0);
+    }
+
+    /// Current translation and rotation offset, from the translation and
+    /// rotation amplitudes scaled by channels 0 to 5.
+    ///
+    /// The rotation is applied in the local space of the transform, after
+    /// its own rotation.
+    #[must_use]
+    pub fn offset(&self) -> (Vec3, Quat) {
+        let channels: [f32; CHANNELS] = core::array::from_fn(|i| self.channel(i) as f32);
+        let translation = self.translation * Vec3::new(channels[0], channels[1], channels[2]);
+        let rotation = Quat::from_euler(
+            EulerRot::XYZ,
+            self.rotation.x * channels[3],
+            self.rotation.y * channels[4],
+            self.rotation.z * channels[5],
+        );
+        (translation, rotation)
+    }
+
+    /// Whether translation or rotation amplitudes are set.
+    #[cfg(feature = "bevy")]
+    fn drives_transform(&self) -> bool {
+        self.translation != Vec3::ZERO || self.rotation != Vec3::ZERO
+    }
+
+    /// Add the current offset to `transform`.
+    #[cfg(feature = "bevy")]
+    fn apply(&mut self, transform: &mut Transform) {
+        let (translation, rotation) = self.offset();
+        transform.translation += translation;
+        transform.rotation *= rotation;
+        self.applied_translation = translation;
+        self.applied_rotation = rotation;
+    }
+
+    /// Remove the offset added by the last [`apply`](Self::apply).
+    #[cfg(feature = "bevy")]
+    fn remove(&mut self, transform: &mut Transform) {
+        transform.translation -= self.applied_translation;
+        transform.rotation *= self.applied_rotation.inverse();
+        self.applied_translation = Vec3::ZERO;
+        self.applied_rotation = Quat::IDENTITY;
+    }
+}
+
+/// Advance signals.
+#[cfg(feature = "bevy")]
+pub(crate) fn update_noise_signals(time: Res<Time>, mut signals: Query<&mut NoiseSignal>) {
+    let delta = time.delta_secs_f64();
+    for mut signal in &mut signals {
+        signal.advance(delta);
+    }
+}
+
+/// Remove last frame's offsets, restoring the transforms without shake.
+///
+/// Includes disabled entities, so an entity disabled mid-shake does not
+/// keep its last offset.
+#[cfg(feature = "bevy")]
+pub(crate) fn remove_noise_signal_offsets(
+    mut signals: Query<(&mut NoiseSignal, &mut Transform), Allow<Disabled>>,
+) {
+    for (mut signal, mut transform) in &mut signals {
+        if signal.applied_translation != Vec3::ZERO || signal.applied_rotation != Quat::IDENTITY {
+            signal.remove(&mut transform);
+        }
+    }
+}
+
+/// Remove the offset of a signal when the component is removed, so its
+/// last shake does not stay in the transform.
+#[cfg(feature = "bevy")]
+pub(crate) fn remove_offset_of_removed_signal(
+    remove: On<Remove, NoiseSignal>,
+    mut signals: Query<(&mut NoiseSignal, &mut Transform), Allow<Disabled>>,
+) {
+    if let Ok((mut signal, mut transform)) = signals.get_mut(remove.entity) {
+        signal.remove(&mut transform);
+    }
+}
+
+/// Add the current offsets to the transforms signals drive.
+#[cfg(feature = "bevy")]
+pub(crate) fn apply_noise_signal_offsets(mut signals: Query<(&mut NoiseSignal, &mut Transform)>) {
+    for (mut signal, mut transform) in &mut signals {
+        if signal.drives_transform() {
+            signal.apply(&mut transform);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trauma_decays_to_silence() {
+        let mut signal = NoiseSignal::new(Noise::new(1).with_scale(1.0))
+            .with_trauma(1.0)
+            .with_decay(2.0);
+
+        signal.advance(0.25);
+        assert!((signal.trauma() - 0.5).abs() < 1e-6);
+        assert!((signal.intensity() - 0.25).abs() < 1e-6);
+
+        signal.advance(1.0);
+        assert!(signal.trauma().abs() < f32::EPSILON);
+        assert!(signal.value().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn channels_are_independent() {
+        let mut signal = NoiseSignal::new(Noise::new(1).with_scale(1.0)).with_frequency(3.0);
+        signal.advance(0.37);
+
+        let values: Vec<f64> = (0..6).map(|i| signal.channel(i)).collect();
+        for (i, a) in values.iter().enumerate() {
+            for b in &values[i + 1..] {
+                assert!((a - b).abs() > 1e-6);
+            }
+        }
+    }
+
+    #[cfg(feature = "bevy")]
+    #[test]
+    fn offsets_do_not_accumulate() {
+        let mut signal = NoiseSignal::new(Noise::new(1).with_scale(1.0))
+            .with_translation(Vec3::ONE)
+            .with_rotation(Vec3::splat(0.1));
+        let mut transform = Transform::from_xyz(1.0, 2.0, 3.0);
+
+        for _ in 0..10 {
+            signal.advance(0.1);
+            signal.apply(&mut transform);
+            assert!(transform.translation.distance(Vec3::new(1.0, 2.0, 3.0)) > 1e-6);
+            signal.remove(&mut transform);
+        }
+
+        assert!(transform.translation.distance(Vec3::new(1.0, 2.0, 3.0)) < 1e-5);
+        assert!(transform.rotation.abs_diff_eq(Quat::IDENTITY, 1e-5));
+    }
+}
@@ -0,0 +1,314 @@
+//! Statistical analysis of [`Noise`] configurations.
+//!
+//! [`NoiseStats`] samples a region of a configured [`Noise`] and reports
+//! its distribution, which is useful when tuning layers and thresholds.
+
+use crate::{Noise, SampleMode};
+use glam::{DVec2, DVec3, UVec2, UVec3};
+
+/// Correlation threshold that defines the autocorrelation length.
+const CORRELATION_THRESHOLD: f64 = std::f64::consts::E.recip();
+
+/// Region sampled by [`NoiseStats::measure`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatsRegion {
+    /// A 2D area sampled on a regular grid.
+    Area {
+        /// Minimum corner in input coordinates.
+        origin: DVec2,
+        /// Size of the area in input coordinates.
+        size: DVec2,
+        /// Number of samples per axis.
+        resolution: UVec2,
+    },
+    /// A 3D volume sampled on a regular grid.
+    Volume {
+        /// Minimum corner in input coordinates.
+        origin: DVec3,
+        /// Size of the volume in input coordinates.
+        size: DVec3,
+        /// Number of samples per axis.
+        resolution: UVec3,
+    },
+}
+
+/// Settings for measuring [`NoiseStats`].
+///
+/// # Examples
+///
+/// ```rust
+/// use msg_noise::math::{DVec2, UVec2};
+/// use msg_noise::{Noise, SampleMode, StatsSettings};
+///
+/// let noise = Noise::new(12345);
+/// let settings = StatsSettings::area(DVec2::ZERO, DVec2::splat(1024.0), UVec2::splat(128))
+///     .with_mode(SampleMode::Normalized)
+///     .with_bins(16);
+///
+/// let stats = noise.stats(&settings);
+/// println!("mean {:.3}, std dev {:.3}", stats.mean(), stats.std_dev());
+/// ```
+#[derive(Clone, Debug)]
+pub struct StatsSettings {
+    region: StatsRegion,
+    mode: SampleMode,
+    bins: usize,
+}
+
+impl StatsSettings {
+    /// Sample a 2D area on a regular grid.
+    #[must_use]
+    pub fn area(origin: DVec2, size: DVec2, resolution: UVec2) -> Self {
+        Self::new(StatsRegion::Area {
+            origin,
+            size,
+            resolution: resolution.max(UVec2::ONE),
+        })
+    }
+
+    /// Sample a 3D volume on a regular grid.
+    #[must_use]
+    pub fn volume(origin: DVec3, size: DVec3, resolution: UVec3) -> Self {
+        Self::new(StatsRegion::Volume {
+            origin,
+            size,
+            resolution: resolution.max(UVec3::ONE),
+        })
+    }
+
+    fn new(region: StatsRegion) -> Self {
+        Self {
+            region,
+            mode: SampleMode::Normalized,
+            bins: 32,
+        }
+    }
+
+    /// Set which sampler to analyze.
+    #[must_use]
+    pub fn with_mode(mut self, mode: SampleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the number of histogram bins.
+    #[must_use]
+    pub fn with_bins(mut self, bins: usize) -> Self {
+        self.bins = bins.max(1);
+        self
+    }
+}
+
+/// Distribution statistics of a sampled noise region.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoiseStats {
+    count: usize,
+    mean: f64,
+    variance: f64,
+    min: f64,
+    max: f64,
+    histogram: Vec<u32>,
+    autocorrelation_length: f64,
+}
+
+impl NoiseStats {
+    /// Sample `noise` over the configured region and compute statistics.
+    #[must_use]
+    pub fn measure(noise: &Noise, settings: &StatsSettings) -> Self {
+        crate::timed(|| Self::measure_untimed(noise, settings))
+    }
+
+    fn measure_untimed(noise: &Noise, settings: &StatsSettings) -> Self {
+        let (samples, row_length, step) = match settings.region {
+            StatsRegion::Area {
+                origin,
+                size,
+                resolution,
+            } => {
+                let step = size / resolution.as_dvec2();
+                let mut samples = Vec::with_capacity((resolution.x * resolution.y) as usize);
+                for j in 0..resolution.y {
+                    let y = origin.y + f64::from(j) * step.y;
+                    for i in 0..resolution.x {
+                        let x = origin.x + f64::from(i) * step.x;
+                        samples.push(noise.sample(settings.mode, x, y));
+                    }
+                }
+                (samples, resolution.x as usize, step.x)
+            }
+            StatsRegion::Volume {
+                origin,
+                size,
+                resolution,
+            } => {
+                let step = size / resolution.as_dvec3();
+                let mut samples =
+                    Vec::with_capacity((resolution.x * resolution.y * resolution.z) as usize);
+                for k in 0..resolution.z {
+                    let z = origin.z + f64::from(k) * step.z;
+                    for j in 0..resolution.y {
+                        let y = origin.y + f64::from(j) * step.y;
+                        for i in 0..resolution.x {
+                            let x = origin.x + f64::from(i) * step.x;
+                            samples.push(noise.sample_3d(settings.mode, x, y, z));
+                        }
+                    }
+                }
+                (samples, resolution.x as usize, step.x)
+            }
+        };
+
+        Self::from_rows(&samples, row_length, step, settings.bins)
+    }
+
+    /// Compute statistics from samples laid out in rows of `row_length`
+    /// values spaced `step` units apart.
+    fn from_rows(samples: &[f64], row_length: usize, step: f64, bins: usize) -> Self {
+        let count = samples.len();
+        let n = count as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let mut histogram = vec![0_u32; bins];
+        let span = max - min;
+        for &value in samples {
+            let bin = if span > 0.0 {
+                (((value - min) / span * bins as f64) as usize).min(bins - 1)
+            } else {
+                0
+            };
+            histogram[bin] += 1;
+        }
+
+        let autocorrelation_length =
+            autocorrelation_length(samples, row_length, mean, variance) * step;
+
+        Self {
+            count,
+            mean,
+            variance,
+            min,
+            max,
+            histogram,
+            autocorrelation_length,
+        }
+    }
+
+    /// Number of samples taken.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Mean sample value.
+    #[must_use]
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance of the samples.
+    #[must_use]
+    pub fn variance(&self) -> f64 {
+        self.variance
+    }
+
+    /// Standard deviation of the samples.
+    #[must_use]
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+
+    /// Smallest sample value.
+    #[must_use]
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// Largest sample value.
+    #[must_use]
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Sample counts in equally sized bins between [`Self::min`] and
+    /// [`Self::max`].
+    #[must_use]
+    pub fn histogram(&self) -> &[u32] {
+        &self.histogram
+    }
+
+    /// Distance along the x axis, in input coordinates, at which the
+    /// autocorrelation of the samples drops below `1/e`.
+    ///
+    /// This approximates the typical feature size. If the correlation never
+    /// drops below the threshold the largest measured lag is returned.
+    #[must_use]
+    pub fn autocorrelation_length(&self) -> f64 {
+        self.autocorrelation_length
+    }
+}
+
+impl Noise {
+    /// Compute [`NoiseStats`] for this noise over the configured region.
+    #[must_use]
+    pub fn stats(&self, settings: &StatsSettings) -> NoiseStats {
+        NoiseStats::measure(self, settings)
+    }
+}
+
+/// Autocorrelation length along rows, in samples.
+fn autocorrelation_length(samples: &[f64], row_length: usize, mean: f64, variance: f64) -> f64 {
+    if variance <= 0.0 || row_length < 2 {
+        return 0.0;
+    }
+
+    let max_lag = (row_length / 2).max(1);
+    let mut previous = 1.0;
+    for lag in 1..=max_lag {
+        let mut sum = 0.0;
+        let mut pairs = 0_usize;
+        for row in samples.chunks_exact(row_length) {
+            for i in 0..row_length - lag {
+                sum += (row[i] - mean) * (row[i + lag] - mean);
+                pairs += 1;
+            }
+        }
+        let correlation = sum / pairs as f64 / variance;
+        if correlation < CORRELATION_THRESHOLD {
+            // Interpolate between the previous lag and this one
+            let fraction = (previous - CORRELATION_THRESHOLD) / (previous - correlation);
+            return (lag - 1) as f64 + fraction;
+        }
+        previous = correlation;
+    }
+
+    max_lag as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_samples_have_zero_variance() {
+        let stats = NoiseStats::from_rows(&[0.5; 64], 8, 1.0, 4);
+
+        assert_eq!(stats.count(), 64);
+        assert!((stats.mean() - 0.5).abs() < f64::EPSILON);
+        assert!(stats.variance().abs() < f64::EPSILON);
+        assert_eq!(stats.histogram(), &[64, 0, 0, 0]);
+        assert!(stats.autocorrelation_length().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn smoother_noise_has_longer_autocorrelation() {
+        let settings = StatsSettings::area(DVec2::ZERO, DVec2::splat(512.0), UVec2::splat(64));
+
+        let smooth = Noise::new(42).with_scale(0.005).stats(&settings);
+        let rough = Noise::new(42).with_scale(0.05).stats(&settings);
+
+        assert!(smooth.autocorrelation_length() > rough.autocorrelation_length());
+    }
+}
@@ -0,0 +1,465 @@
+//! Bevy [`Image`] generation from [`Noise`].
+//!
+//! Images are built entirely on the CPU, so they can be generated in
+//! headless apps, tests and offline tools and then added to
+//! [`Assets<Image>`](bevy::asset::Assets) or saved by the caller.
+
+use crate::{Noise, SampleMode, SampleRegion};
+use bevy::asset::RenderAssetUsages;
+use bevy::color::{Color, ColorToPacked, LinearRgba, Mix, Srgba};
+use bevy::image::Image;
+use bevy::math::UVec2;
+use wgpu_types::{Extent3d, TextureDimension, TextureFormat};
+
+/// Pixel format of a generated noise image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NoiseImageFormat {
+    /// 8-bit single channel, values clamped to 0.0 to 1.0.
+    R8,
+    /// 16-bit single channel, values clamped to 0.0 to 1.0.
+    R16,
+    /// 32-bit float single channel, values written unchanged.
+    R32F,
+    /// 8-bit sRGB color, mapped through a [`ColorRamp`], or through
+    /// [`ColorRamp::grayscale`] without one.
+    #[default]
+    Rgba8,
+}
+
+impl NoiseImageFormat {
+    /// Texture format for a single noise layer.
+    fn texture_format(self) -> TextureFormat {
+        match self {
+            Self::R8 => TextureFormat::R8Unorm,
+            Self::R16 => TextureFormat::R16Unorm,
+            Self::R32F => TextureFormat::R32Float,
+            Self::Rgba8 => TextureFormat::Rgba8UnormSrgb,
+        }
+    }
+
+    /// Texture format for up to four layers packed into RGBA channels.
+    fn packed_texture_format(self) -> TextureFormat {
+        match self {
+            Self::R8 | Self::Rgba8 => TextureFormat::Rgba8Unorm,
+            Self::R16 => TextureFormat::Rgba16Unorm,
+            Self::R32F => TextureFormat::Rgba32Float,
+        }
+    }
+}
+
+/// A color gradient used to visualize noise values.
+///
+/// Stops are interpolated in linear color space.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorRamp {
+    stops: Vec<(f32, LinearRgba)>,
+}
+
+impl Default for ColorRamp {
+    fn default() -> Self {
+        Self::grayscale()
+    }
+}
+
+impl ColorRamp {
+    /// Create an empty ramp. Add stops with [`Self::with_stop`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self { stops: Vec::new() }
+    }
+
+    /// Black at 0.0 to white at 1.0.
+    #[must_use]
+    pub fn grayscale() -> Self {
+        Self::new()
+            .with_stop(0.0, Color::BLACK)
+            .with_stop(1.0, Color::WHITE)
+    }
+
+    /// Deep water, shallows, sand, grass, rock and snow.
+    #[must_use]
+    pub fn terrain() -> Self {
+        Self::new()
+            .with_stop(0.0, Color::srgb_u8(16, 42, 96))
+            .with_stop(0.4, Color::srgb_u8(52, 112, 176))
+            .with_stop(0.45, Color::srgb_u8(222, 206, 150))
+            .with_stop(0.55, Color::srgb_u8(84, 150, 62))
+            .with_stop(0.75, Color::srgb_u8(112, 102, 92))
+            .with_stop(0.9, Color::srgb_u8(245, 245, 250))
+    }
+
+    /// Add a color stop at `position`, keeping stops sorted.
+    #[must_use]
+    pub fn with_stop(mut self, position: f32, color: impl Into<Color>) -> Self {
+        let index = self.stops.partition_point(|(p, _)| *p <= position);
+        self.stops
+            .insert(index, (position, color.into().to_linear()));
+        self
+    }
+
+    /// Sample the ramp at `t`. Values outside the stops take the nearest
+    /// stop's color.
+    #[must_use]
+    pub fn sample(&self, t: f32) -> Color {
+        Color::LinearRgba(self.sample_linear(t))
+    }
+
+    fn sample_linear(&self, t: f32) -> LinearRgba {
+        let (Some(&(first, first_color)), Some(&(last, last_color))) =
+            (self.stops.first(), self.stops.last())
+        else {
+            return LinearRgba::BLACK;
+        };
+
+        if t <= first {
+            return first_color;
+        }
+        if t >= last {
+            return last_color;
+        }
+
+        let upper = self.stops.partition_point(|(p, _)| *p <= t);
+        let (p0, c0) = self.stops[upper - 1];
+        let (p1, c1) = self.stops[upper];
+        let span = p1 - p0;
+        if span <= 0.0 {
+            return c1;
+        }
+        c0.mix(&c1, (t - p0) / span)
+    }
+}
+
+/// Settings for generating an [`Image`] from noise.
+///
+/// # Examples
+///
+/// ```rust
+/// use bevy::math::{DVec2, UVec2};
+/// use msg_noise::{ColorRamp, Noise, NoiseImage, NoiseImageFormat, SampleRegion};
+///
+/// let terrain = Noise::new(12345);
+/// let minimap = NoiseImage::new(
+///     UVec2::splat(128),
+///     SampleRegion::new(DVec2::ZERO, DVec2::splat(4096.0)),
+///     NoiseImageFormat::Rgba8,
+/// )
+/// .with_ramp(ColorRamp::terrain())
+/// .build(&terrain);
+///
+/// assert_eq!(minimap.width(), 128);
+/// ```
+#[derive(Clone, Debug)]
+pub struct NoiseImage {
+    size: UVec2,
+    region: SampleRegion,
+    format: NoiseImageFormat,
+    mode: SampleMode,
+    ramp: Option<ColorRamp>,
+    mipmaps: bool,
+    asset_usage: RenderAssetUsages,
+}
+
+impl NoiseImage {
+    /// Create settings for an image of `size` pixels covering `region`.
+    ///
+    /// Each pixel samples the center of its cell. Row 0 corresponds to the
+    /// minimum y of the region.
+    #[must_use]
+    pub fn new(size: UVec2, region: SampleRegion, format: NoiseImageFormat) -> Self {
+        Self {
+            size: size.max(UVec2::ONE),
+            region,
+            format,
+            mode: SampleMode::Normalized,
+            ramp: None,
+            mipmaps: false,
+            asset_usage: RenderAssetUsages::default(),
+        }
+    }
+
+    /// Set which sampler to evaluate. Defaults to [`SampleMode::Normalized`].
+    #[must_use]
+    pub fn with_mode(mut self, mode: SampleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Map values through a color ramp. Only used by
+    /// [`NoiseImageFormat::Rgba8`] single-layer images.
+    #[must_use]
+    pub fn with_ramp(mut self, ramp: ColorRamp) -> Self {
+        self.ramp = Some(ramp);
+        self
+    }
+
+    /// Generate a full mipmap chain.
+    #[must_use]
+    pub fn with_mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    /// Set where the image asset is used. Defaults to both the main and
+    /// render worlds.
+    #[must_use]
+    pub fn with_asset_usage(mut self, asset_usage: RenderAssetUsages) -> Self {
+        self.asset_usage = asset_usage;
+        self
+    }
+
+    /// Generate an image from a single noise layer.
+    #[must_use]
+    pub fn build(&self, noise: &Noise) -> Image {
+        self.build_with(|x, y| noise.sample(self.mode, x, y))
+    }
+
+    /// Generate an image from an arbitrary function of x and y, e.g. an
+    /// animated or combined noise. The sampler mode is not used.
+    #[must_use]
+    pub fn build_with(&self, f: impl Fn(f64, f64) -> f64) -> Image {
+        crate::timed(|| self.build_values(self.sample_fn(f)))
+    }
+
+    /// Regenerate the pixels of an existing image from a function of x and
+    /// y, reusing its pixel buffer.
+    ///
+    /// The sampler and the rest of the texture descriptor, such as usages
+    /// and label, are kept; only the size, format and mip level count are
+    /// set from these settings. Used to animate images every frame.
+    pub fn update_with(&self, image: &mut Image, f: impl Fn(f64, f64) -> f64) {
+        crate::timed(|| {
+            let levels = self.mip_chain(self.sample_fn(f), 1);
+            let data = image.data.get_or_insert_with(Vec::new);
+            data.clear();
+            self.encode_levels(&levels, data);
+
+            let descriptor = &mut image.texture_descriptor;
+            descriptor.size = self.extent();
+            descriptor.dimension = TextureDimension::D2;
+            descriptor.format = self.format.texture_format();
+            descriptor.mip_level_count = levels.len() as u32;
+        });
+    }
+
+    /// Generate an image from values sampled at every pixel center, row by
+    /// row.
+    pub(crate) fn build_values(&self, values: Vec<f32>) -> Image {
+        let levels = self.mip_chain(values, 1);
+
+        let mut data = Vec::new();
+        self.encode_levels(&levels, &mut data);
+
+        self.finish(data, self.format.texture_format(), levels.len())
+    }
+
+    /// Encode single-layer mip levels into `data`.
+    fn encode_levels(&self, levels: &[(UVec2, Vec<f32>)], data: &mut Vec<u8>) {
+        for (_, level) in levels {
+            for &value in level {
+                self.encode_single(value, data);
+            }
+        }
+    }
+
+    /// Generate an image with up to four noise layers packed into the red,
+    /// green, blue and alpha channels.
+    ///
+    /// Missing channels are filled with 0.0, except alpha which is filled
+    /// with 1.0. Color ramps are not applied to packed images.
+    #[must_use]
+    pub fn build_packed(&self, layers: [Option<&Noise>; 4]) -> Image {
+        let pixels = (self.size.x * self.size.y) as usize;
+        let mut values = vec![0.0_f32; pixels * 4];
+        for (channel, layer) in layers.iter().enumerate() {
+            let channel_values = match layer {
+                Some(noise) => crate::timed(|| self.sample_layer(noise)),
+                None if channel == 3 => vec![1.0; pixels],
+                None => continue,
+            };
+            for (pixel, value) in channel_values.into_iter().enumerate() {
+                values[pixel * 4 + channel] = value;
+            }
+        }
+
+        let levels = self.mip_chain(values, 4);
+        let mut data = Vec::new();
+        for (_, level) in &levels {
+            for &value in level {
+                encode_channel(self.format, value, &mut data);
+            }
+        }
+
+        self.finish(data, self.format.packed_texture_format(), levels.len())
+    }
+
+    /// Sample a noise layer at every pixel center.
+    fn sample_layer(&self, noise: &Noise) -> Vec<f32> {
+        self.sample_fn(|x, y| noise.sample(self.mode, x, y))
+    }
+
+    /// Evaluate `f` at every pixel center.
+    pub(crate) fn sample_fn(&self, f: impl Fn(f64, f64) -> f64) -> Vec<f32> {
+        let mut values = Vec::with_capacity((self.size.x * self.size.y) as usize);
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let point = self.region.cell_center(UVec2::new(x, y), self.size);
+                values.push(f(point.x, point.y) as f32);
+            }
+        }
+        values
+    }
+
+    /// Build the mip chain for interleaved values with `channels` values
+    /// per pixel, using a 2x2 box filter.
+    fn mip_chain(&self, base: Vec<f32>, channels: usize) -> Vec<(UVec2, Vec<f32>)> {
+        let mut levels = vec![(self.size, base)];
+        if !self.mipmaps {
+            return levels;
+        }
+
+        while let Some((size, values)) = levels.last()
+            && size.max_element() > 1
+        {
+            let next = (*size / 2).max(UVec2::ONE);
+            let mut downsampled = Vec::with_capacity((next.x * next.y) as usize * channels);
+            for y in 0..next.y {
+                for x in 0..next.x {
+                    for channel in 0..channels {
+                        let mut sum = 0.0;
+                        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                            let sx = (x * 2 + dx).min(size.x - 1);
+                            let sy = (y * 2 + dy).min(size.y - 1);
+                            sum += values[(sy * size.x + sx) as usize * channels + channel];
+                        }
+                        downsampled.push(sum * 0.25);
+                    }
+                }
+            }
+            levels.push((next, downsampled));
+        }
+
+        levels
+    }
+
+    /// Encode a single-layer value into `data`.
+    fn encode_single(&self, value: f32, data: &mut Vec<u8>) {
+        match self.format {
+            NoiseImageFormat::Rgba8 => {
+                let color = match &self.ramp {
+                    Some(ramp) => ramp.sample_linear(value),
+                    // Same as ColorRamp::grayscale, interpolated in linear space
+                    None => {
+                        let gray = value.clamp(0.0, 1.0);
+                        LinearRgba::rgb(gray, gray, gray)
+                    }
+                };
+                data.extend_from_slice(&Srgba::from(color).to_u8_array());
+            }
+            format => encode_channel(format, value, data),
+        }
+    }
+
+    fn extent(&self) -> Extent3d {
+        Extent3d {
+            width: self.size.x,
+            height: self.size.y,
+            depth_or_array_layers: 1,
+        }
+    }
+
+    fn finish(&self, data: Vec<u8>, format: TextureFormat, mip_levels: usize) -> Image {
+        let mut image = Image::new_uninit(
+            self.extent(),
+            TextureDimension::D2,
+            format,
+            self.asset_usage,
+        );
+        image.texture_descriptor.mip_level_count = mip_levels as u32;
+        image.data = Some(data);
+        image
+    }
+}
+
+/// Encode one channel value for `format`.
+fn encode_channel(format: NoiseImageFormat, value: f32, data: &mut Vec<u8>) {
+    match format {
+        NoiseImageFormat::R8 | NoiseImageFormat::Rgba8 => {
+            data.push((value.clamp(0.0, 1.0) * f32::from(u8::MAX)).round() as u8);
+        }
+        NoiseImageFormat::R16 => {
+            let value = (value.clamp(0.0, 1.0) * f32::from(u16::MAX)).round() as u16;
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        NoiseImageFormat::R32F => data.extend_from_slice(&value.to_le_bytes()),
+    }
+}
+
+impl Noise {
+    /// Generate an image of `size` pixels covering `region` using
+    /// normalized noise values.
+    ///
+    /// Use [`NoiseImage`] for color ramps, other samplers, channel packing
+    /// and mipmaps.
+    #[must_use]
+    pub fn to_image(&self, size: UVec2, region: SampleRegion, format: NoiseImageFormat) -> Image {
+        NoiseImage::new(size, region, format).build(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::DVec2;
+
+    fn region() -> SampleRegion {
+        SampleRegion::new(DVec2::ZERO, DVec2::splat(1000.0))
+    }
+
+    #[test]
+    fn ramp_interpolates_between_stops() {
+        let ramp = ColorRamp::grayscale();
+        let mid = ramp.sample_linear(0.5);
+
+        assert!((mid.red - 0.5).abs() < 1e-6);
+        assert_eq!(ramp.sample_linear(-1.0), LinearRgba::BLACK);
+        assert_eq!(ramp.sample_linear(2.0), LinearRgba::WHITE);
+    }
+
+    #[test]
+    fn default_gray_matches_grayscale_ramp() {
+        let settings = NoiseImage::new(UVec2::splat(16), region(), NoiseImageFormat::Rgba8);
+        let noise = Noise::new(42);
+
+        let plain = settings.build(&noise);
+        let ramp = settings
+            .clone()
+            .with_ramp(ColorRamp::grayscale())
+            .build(&noise);
+        assert_eq!(plain.data, ramp.data);
+    }
+
+    #[test]
+    fn mipmaps_halve_until_single_pixel() {
+        let image = NoiseImage::new(UVec2::new(8, 4), region(), NoiseImageFormat::R8)
+            .with_mipmaps(true)
+            .build(&Noise::new(42));
+
+        // 8x4 + 4x2 + 2x1 + 1x1
+        assert_eq!(image.texture_descriptor.mip_level_count, 4);
+        assert_eq!(image.data.as_ref().map(Vec::len), Some(32 + 8 + 2 + 1));
+    }
+
+    #[test]
+    fn packed_image_fills_missing_alpha() {
+        let noise = Noise::new(42);
+        let image = NoiseImage::new(UVec2::splat(4), region(), NoiseImageFormat::R8)
+            .build_packed([Some(&noise), None, None, None]);
+
+        let data = image.data.as_ref().expect("image data");
+        assert_eq!(data.len(), 4 * 4 * 4);
+        for pixel in data.chunks_exact(4) {
+            assert_eq!(pixel[1], 0);
+            assert_eq!(pixel[3], u8::MAX);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Per-tile sampling for tile-based games.
+//!
+//! Tilemaps address the world by integer tile coordinates. [`Noise::sample_tile`]
+//! samples smooth noise once per tile, and the [`NoiseSource`] helpers return
+//! white noise: stateless hashes and random values that depend only on the
+//! base seed, a key and the tile, so they can be queried in any order.
+
+use crate::scatter::TileRng;
+use crate::{Noise, NoiseSource, hash_combine};
+use glam::{DVec2, IVec2};
+
+impl Noise {
+    /// Get scaled noise at the center of `tile`.
+    ///
+    /// Tiles are one input unit wide, so this is [`Self::get_scaled`] at
+    /// `tile + 0.5`, matching the cell centers used when rasterizing a
+    /// [`SampleRegion`](crate::SampleRegion) with one cell per tile.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use msg_noise::math::IVec2;
+    /// use msg_noise::NoiseSource;
+    ///
+    /// let source = NoiseSource::new(12345);
+    /// let fertility = source.create(0x4645_5254).with_scale(0.1);
+    /// let value = fertility.sample_tile(IVec2::new(3, -7));
+    /// assert_eq!(value, fertility.get_scaled(3.5, -6.5));
+    /// ```
+    #[must_use]
+    pub fn sample_tile(&self, tile: IVec2) -> f64 {
+        let center = tile.as_dvec2() + DVec2::splat(0.5);
+        self.get_scaled(center.x, center.y)
+    }
+}
+
+impl NoiseSource {
+    /// Hash of `tile` for `key`.
+    ///
+    /// Keys are combined with the base seed the same way as in
+    /// [`Self::create`], so different keys give independent hashes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use msg_noise::math::IVec2;
+    /// use msg_noise::NoiseSource;
+    ///
+    /// const VARIANT: u32 = 0x5641_5249;
+    ///
+    /// let source = NoiseSource::new(12345);
+    /// let variant = source.hash_tile(IVec2::new(4, 2), VARIANT) % 3;
+    /// assert_eq!(variant, NoiseSource::new(12345).hash_tile(IVec2::new(4, 2), VARIANT) % 3);
+    /// ```
+    #[must_use]
+    pub fn hash_tile(&self, tile: IVec2, key: u32) -> u64 {
+        TileRng::new(hash_combine(self.seed, key), tile).next_u64()
+    }
+
+    /// Uniform random value in `0.0..1.0` for `tile` and `key`.
+    #[must_use]
+    pub fn random_f64_at(&self, tile: IVec2, key: u32) -> f64 {
+        (self.hash_tile(tile, key) >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Uniform random value in `min..max` for `tile` and `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use msg_noise::math::IVec2;
+    /// use msg_noise::NoiseSource;
+    ///
+    /// let source = NoiseSource::new(12345);
+    /// let rotation = source.random_range_at(IVec2::new(8, 1), 0x524F_5441, 0.0, 360.0);
+    /// assert!((0.0..360.0).contains(&rotation));
+    /// ```
+    #[must_use]
+    pub fn random_range_at(&self, tile: IVec2, key: u32, min: f64, max: f64) -> f64 {
+        min + self.random_f64_at(tile, key) * (max - min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SampleRegion;
+    use glam::UVec2;
+
+    #[test]
+    fn hashes_differ_between_tiles_and_keys() {
+        let source = NoiseSource::new(7);
+        let mut hashes = Vec::new();
+        for key in 0..4 {
+            for y in -4..4 {
+                for x in -4..4 {
+                    hashes.push(source.hash_tile(IVec2::new(x, y), key));
+                }
+            }
+        }
+        let count = hashes.len();
+        hashes.sort_unstable();
+        hashes.dedup();
+        assert_eq!(hashes.len(), count);
+    }
+
+    #[test]
+    fn random_values_are_uniform() {
+        let source = NoiseSource::new(7);
+        let mut buckets = [0_u32; 10];
+        for y in 0..100 {
+            for x in 0..100 {
+                let value = source.random_f64_at(IVec2::new(x, y), 1);
+                assert!((0.0..1.0).contains(&value));
+                buckets[(value * 10.0) as usize] += 1;
+            }
+        }
+        // 1000 expected per bucket
+        assert!(buckets.iter().all(|count| (850..1150).contains(count)));
+    }
+
+    #[test]
+    fn sample_tile_matches_cell_centers() {
+        let noise = Noise::new(7).with_scale(0.3);
+        let region = SampleRegion::new(DVec2::new(-4.0, 2.0), DVec2::splat(8.0));
+        for y in 0..8 {
+            for x in 0..8 {
+                let center = region.cell_center(UVec2::new(x, y), UVec2::splat(8));
+                let tile = IVec2::new(x as i32 - 4, y as i32 + 2);
+                assert_eq!(
+                    noise.sample_tile(tile),
+                    noise.get_scaled(center.x, center.y)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! End-to-end tests of the `msg_noise` command-line tool.
+
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn msg_noise(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_msg_noise"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("msg_noise_cli_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+#[test]
+fn render_writes_png_of_requested_size() {
+    let path = temp_path("render.png");
+    let output = msg_noise(&[
+        "render",
+        "--seed",
+        "12345",
+        "--key",
+        "0x5445_5252",
+        "--resolution",
+        "32,16",
+        "-o",
+        path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+
+    let png = image::open(&path).unwrap();
+    assert_eq!((png.width(), png.height()), (32, 16));
+    assert_eq!(png.color(), image::ColorType::L16);
+}
+
+#[test]
+fn render_raw_matches_library_sampling() {
+    let path = temp_path("render.raw");
+    let output = msg_noise(&[
+        "render",
+        "--seed",
+        "7",
+        "--key",
+        "3",
+        "--resolution",
+        "4",
+        "-o",
+        path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+
+    let raw = std::fs::read(&path).unwrap();
+    assert_eq!(raw.len(), 4 * 4 * 4);
+    let noise = msg_noise::NoiseSource::new(7).create(3);
+    let expected = msg_noise::NoiseRecipe::new(3).sample(&noise, 0.5, 0.5) as f32;
+    assert_eq!(raw[..4], expected.to_le_bytes());
+}
+
+#[test]
+fn diff_reports_identical_and_differing_configurations() {
+    let same = msg_noise(&["diff", "--seed", "1", "--resolution", "8", "--b-seed", "1"]);
+    assert!(same.status.success(), "{same:?}");
+    assert!(String::from_utf8_lossy(&same.stdout).contains("differing: 0"));
+
+    let other = msg_noise(&["diff", "--seed", "1", "--resolution", "8", "--b-seed", "2"]);
+    assert_eq!(other.status.code(), Some(1));
+
+    let invalid = msg_noise(&["stats", "--algorithm", "cellular"]);
+    assert_eq!(invalid.status.code(), Some(2));
+}
@@ -0,0 +1,343 @@
+//! Golden sample tables for each output version.
+//!
+//! Saved worlds are regenerated from their seeds, so the values produced
+//! for a seed must never change silently, e.g. when upgrading the `noise`
+//! crate. When an algorithm change is unavoidable, bump
+//! `NOISE_OUTPUT_VERSION`, keep the old table and check it through
+//! `NoiseSource::with_output_version`, and add a table for the new
+//! version.
+
+use msg_noise::{
+    CalibrationSettings, FixedNoise, NOISE_OUTPUT_VERSION, Noise, NoiseSource, SampleMode,
+    UnsupportedOutputVersion,
+};
+
+const KEY: u32 = 0x474F_4C44;
+
+const POINTS: [(f64, f64, f64); 3] = [
+    (3.7, -12.25, 0.5),
+    (250.125, 91.5, -33.3),
+    (-4096.75, 777.7, 1234.5),
+];
+
+const MODES: [(&str, SampleMode); 9] = [
+    ("Raw", SampleMode::Raw),
+    ("Normalized", SampleMode::Normalized),
+    ("Absolute", SampleMode::Absolute),
+    ("Scaled", SampleMode::Scaled),
+    ("AbsoluteScaled", SampleMode::AbsoluteScaled),
+    ("Calibrated", SampleMode::Calibrated),
+    ("Equalized", SampleMode::Equalized),
+    (
+        "Fractal",
+        SampleMode::Fractal {
+            octaves: 4,
+            persistence: 0.5,
+            lacunarity: 2.0,
+        },
+    ),
+    (
+        "FractalScaled",
+        SampleMode::FractalScaled {
+            octaves: 4,
+            persistence: 0.5,
+            lacunarity: 2.0,
+        },
+    ),
+];
+
+/// `(seed, mode, 2D values, 3D values)` at [`POINTS`] for version 1.
+const GOLDEN_V1: [(u32, &str, [f64; 3], [f64; 3]); 27] = [
+    (
+        0x0,
+        "Raw",
+        [
+            0.48211902005636675,
+            0.3846262841857831,
+            -0.12535894564700947,
+        ],
+        [
+            -0.040847863000198735,
+            0.3536096311564743,
+            -0.10047435832759372,
+        ],
+    ),
+    (
+        0x0,
+        "Normalized",
+        [0.7410595100281834, 0.6923131420928915, 0.4373205271764953],
+        [0.47957606849990064, 0.6768048155782371, 0.4497628208362031],
+    ),
+    (
+        0x0,
+        "Absolute",
+        [0.48211902005636675, 0.3846262841857831, 0.12535894564700947],
+        [
+            0.040847863000198735,
+            0.3536096311564743,
+            0.10047435832759372,
+        ],
+    ),
+    (
+        0x0,
+        "Scaled",
+        [34.463570601691, 31.538788525573494, 16.239231630589718],
+        [18.77456410999404, 30.60828893469423, 16.985769250172186],
+    ),
+    (
+        0x0,
+        "AbsoluteScaled",
+        [18.927141203382007, 13.077577051146985, -2.4784632611794315],
+        [-7.549128219988075, 11.216577869388459, -3.9715385003443773],
+    ),
+    (
+        0x0,
+        "Calibrated",
+        [0.7410595100281834, 0.6923131420928915, 0.4373205271764953],
+        [0.47957606849990064, 0.6768048155782371, 0.4497628208362031],
+    ),
+    (
+        0x0,
+        "Equalized",
+        [0.8554609284559953, 0.8137829188188986, 0.37387910284011017],
+        [0.45658351392944063, 0.7942191582408663, 0.39596315410653976],
+    ),
+    (
+        0x0,
+        "Fractal",
+        [
+            0.13039156072986152,
+            0.2983967633215984,
+            -0.01280908956928376,
+        ],
+        [
+            -0.00585566877748371,
+            0.1737381667483837,
+            -0.06839503723306997,
+        ],
+    ),
+    (
+        0x0,
+        "FractalScaled",
+        [23.911746821895846, 28.951902899647955, 19.61572731292149],
+        [19.82432993667549, 25.212145002451514, 17.948148883007903],
+    ),
+    (
+        0x3039,
+        "Raw",
+        [-0.10209638967458148, 0.92830599472265, 0.359426473190766],
+        [
+            0.3556045200165521,
+            -0.16096512662189613,
+            0.01970485470180368,
+        ],
+    ),
+    (
+        0x3039,
+        "Normalized",
+        [0.44895180516270927, 0.964152997361325, 0.679713236595383],
+        [0.677802260008276, 0.41951743668905195, 0.5098524273509019],
+    ),
+    (
+        0x3039,
+        "Absolute",
+        [0.10209638967458148, 0.92830599472265, 0.359426473190766],
+        [0.3556045200165521, 0.16096512662189613, 0.01970485470180368],
+    ),
+    (
+        0x3039,
+        "Scaled",
+        [16.937108309762557, 47.8491798416795, 30.78279419572298],
+        [30.668135600496562, 15.171046201343117, 20.591145641054112],
+    ),
+    (
+        0x3039,
+        "AbsoluteScaled",
+        [-3.874216619525111, 45.698359683359, 11.56558839144596],
+        [11.336271200993124, -0.342092402686232, -8.81770871789178],
+    ),
+    (
+        0x3039,
+        "Calibrated",
+        [0.44895180516270927, 0.964152997361325, 0.679713236595383],
+        [0.677802260008276, 0.41951743668905195, 0.5098524273509019],
+    ),
+    (
+        0x3039,
+        "Equalized",
+        [0.3967773104613299, 0.9899325464214778, 0.7873800564889887],
+        [0.7847613106363795, 0.35133577133321287, 0.5202751946790488],
+    ),
+    (
+        0x3039,
+        "Fractal",
+        [-0.09221808047717567, 0.4636160362639197, 0.1596842077649298],
+        [
+            0.12641228051194084,
+            -0.10504328092431983,
+            0.027487865694182982,
+        ],
+    ),
+    (
+        0x3039,
+        "FractalScaled",
+        [17.23345758568473, 33.90848108791759, 24.790526232947897],
+        [23.792368415358226, 16.848701572270407, 20.82463597082549],
+    ),
+    (
+        0xDEADBEEF,
+        "Raw",
+        [
+            -0.42587904336714794,
+            0.08909029604525914,
+            -0.3123328387659317,
+        ],
+        [0.0951588290360257, -0.2113377589067565, 0.2734232601398508],
+    ),
+    (
+        0xDEADBEEF,
+        "Normalized",
+        [0.287060478316426, 0.5445451480226295, 0.3438335806170342],
+        [0.5475794145180128, 0.3943311205466218, 0.6367116300699254],
+    ),
+    (
+        0xDEADBEEF,
+        "Absolute",
+        [0.42587904336714794, 0.08909029604525914, 0.3123328387659317],
+        [0.0951588290360257, 0.2113377589067565, 0.2734232601398508],
+    ),
+    (
+        0xDEADBEEF,
+        "Scaled",
+        [7.223628698985561, 22.67270888135777, 10.630014837022049],
+        [22.854764871080768, 13.659867232797307, 28.202697804195523],
+    ),
+    (
+        0xDEADBEEF,
+        "AbsoluteScaled",
+        [15.552742602028875, -4.654582237284451, 8.739970325955902],
+        [-4.290470257838458, 2.6802655344053896, 6.405395608391046],
+    ),
+    (
+        0xDEADBEEF,
+        "Calibrated",
+        [0.287060478316426, 0.5445451480226295, 0.3438335806170342],
+        [0.5475794145180128, 0.3943311205466218, 0.6367116300699254],
+    ),
+    (
+        0xDEADBEEF,
+        "Equalized",
+        [0.17549126873414939, 0.5863286839469125, 0.24290363328093387],
+        [0.5917918880326483, 0.3094046967232688, 0.7247206541684806],
+    ),
+    (
+        0xDEADBEEF,
+        "Fractal",
+        [
+            0.01685344240399637,
+            0.10237225131999078,
+            -0.4127150514145718,
+        ],
+        [0.16010319786796137, -0.2404387299281031, 0.1743133021724213],
+    ),
+    (
+        0xDEADBEEF,
+        "FractalScaled",
+        [20.50560327211989, 23.07116753959972, 7.618548457562845],
+        [24.803095936038844, 12.786838102156906, 25.229399065172636],
+    ),
+];
+
+/// `(seed, 1D values)` at the x coordinates of [`POINTS`] for version 1.
+const GOLDEN_1D_V1: [(u32, [f64; 3]); 3] = [
+    (
+        0x0,
+        [
+            -0.21546608203180334,
+            -0.32321749717483483,
+            0.004669512390529861,
+        ],
+    ),
+    (0x3039, [0.28854360976482896, 1.0, -0.33356017751147277]),
+    (
+        0xDEADBEEF,
+        [0.27281967441363386, 0.4808750821452956, 0.6291017390030643],
+    ),
+];
+
+/// Values may differ in the last bits between platforms, but algorithm
+/// changes move them far more.
+const TOLERANCE: f64 = 1e-12;
+
+fn golden_noise(seed: u32, version: u32) -> Noise {
+    NoiseSource::new(seed)
+        .with_output_version(version)
+        .create(KEY)
+        .with_scale(0.05)
+        .with_range(-10.0, 50.0)
+        .calibrated(&CalibrationSettings::default())
+}
+
+fn assert_close(actual: f64, expected: f64, context: &str) {
+    assert!(
+        (actual - expected).abs() <= TOLERANCE,
+        "{context}: expected {expected:?}, got {actual:?}"
+    );
+}
+
+#[test]
+fn version_1_matches_golden_tables() {
+    for (seed, name, expected_2d, expected_3d) in GOLDEN_V1 {
+        let noise = golden_noise(seed, 1);
+        let mode = MODES.iter().find(|(mode, _)| *mode == name).unwrap().1;
+        for (i, &(x, y, z)) in POINTS.iter().enumerate() {
+            let context = format!("seed {seed:#X} {name} at {i}");
+            assert_close(noise.sample(mode, x, y), expected_2d[i], &context);
+            assert_close(noise.sample_3d(mode, x, y, z), expected_3d[i], &context);
+        }
+    }
+
+    for (seed, expected) in GOLDEN_1D_V1 {
+        let noise = golden_noise(seed, 1);
+        for (i, &(x, ..)) in POINTS.iter().enumerate() {
+            let context = format!("seed {seed:#X} 1D at {i}");
+            assert_close(noise.get_raw_1d(x), expected[i], &context);
+        }
+    }
+}
+
+#[test]
+fn output_version_defaults_to_current_and_is_inherited() {
+    let source = NoiseSource::new(1);
+    assert_eq!(source.output_version(), NOISE_OUTPUT_VERSION);
+
+    let pinned = source.with_output_version(1);
+    assert_eq!(pinned.create(KEY).output_version(), 1);
+    assert_eq!(pinned.create_salted(KEY, 2).output_version(), 1);
+    assert_eq!(pinned.create_fixed(KEY).output_version(), 1);
+    assert_eq!(FixedNoise::new(1).output_version(), NOISE_OUTPUT_VERSION);
+
+    // Unknown versions are reported instead of silently producing another
+    // version's output
+    for version in [0, NOISE_OUTPUT_VERSION + 1] {
+        let result = NoiseSource::new(1).try_with_output_version(version);
+        assert_eq!(result.err(), Some(UnsupportedOutputVersion(version)));
+    }
+    assert!(
+        NoiseSource::new(1)
+            .try_with_output_version(NOISE_OUTPUT_VERSION)
+            .is_ok()
+    );
+}
+
+#[test]
+#[should_panic(expected = "unsupported noise output version")]
+fn unsupported_output_version_panics() {
+    let _ = NoiseSource::new(1).with_output_version(NOISE_OUTPUT_VERSION + 1);
+}
+
+#[test]
+#[should_panic(expected = "unsupported noise output version")]
+fn unsupported_fixed_output_version_panics() {
+    let _ = FixedNoise::new(1).with_output_version(0);
+}
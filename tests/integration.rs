@@ -1,5 +1,7 @@
 //! Integration tests for `msg_noise` with Bevy 0.18
 
+#![cfg(feature = "bevy")]
+
 use bevy::prelude::*;
 use msg_noise::prelude::*;
 use msg_rng::prelude::*;
@@ -221,6 +223,60 @@ fn noise_range_configuration() {
     );
 }
 
+#[test]
+fn scaled_samplers_respect_range() {
+    let noise = Noise::new(42).with_range(-10.0, 10.0).with_clamp(true);
+
+    for x in -20..20 {
+        for y in -20..20 {
+            let (x, y) = (f64::from(x) * 7.0, f64::from(y) * 7.0);
+            for value in [
+                noise.get_scaled(x, y),
+                noise.get_scaled_3d(x, y, 3.0),
+                noise.get_absolute_scaled(x, y),
+            ] {
+                assert!(
+                    (-10.0..=10.0).contains(&value),
+                    "Scaled value {value} out of range at ({x}, {y})"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn inverted_range_flips_output() {
+    let normal = Noise::new(42).with_range(0.0, 1.0);
+    let inverted = Noise::new(42).with_range(1.0, 0.0);
+
+    for i in 0..10 {
+        let x = f64::from(i) * 11.0;
+        let sum = normal.get_scaled(x, 0.0) + inverted.get_scaled(x, 0.0);
+        assert!(
+            (sum - 1.0).abs() < 1e-12,
+            "Inverted range should mirror the normal range at x={x}"
+        );
+    }
+}
+
+#[test]
+fn remap_curve_applies_before_range() {
+    let squared = Noise::new(42)
+        .with_range(0.0, 50.0)
+        .with_curve(RemapCurve::Power(2.0));
+    let custom = Noise::new(42)
+        .with_range(0.0, 50.0)
+        .with_curve(RemapCurve::custom(|t| t * t));
+
+    for i in 0..10 {
+        let x = f64::from(i) * 5.0;
+        let normalized = squared.get_normalized(x, 0.0);
+        let expected = normalized * normalized * 50.0;
+        assert!((squared.get_scaled(x, 0.0) - expected).abs() < 1e-9);
+        assert!((custom.get_scaled(x, 0.0) - expected).abs() < 1e-9);
+    }
+}
+
 #[test]
 fn noise_offset_affects_coordinates() {
     let noise1 = Noise::new(42).with_offset(0.0);
@@ -252,6 +308,50 @@ fn noise_offset_affects_coordinates() {
     );
 }
 
+#[test]
+fn noise_rotation_rotates_input_plane() {
+    use bevy::math::DQuat;
+
+    let plain = Noise::new(42);
+    let rotated = Noise::new(42).with_rotation(DQuat::from_rotation_z(std::f64::consts::FRAC_PI_2));
+
+    // A quarter turn about z maps (x, y) to (-y, x)
+    for i in 0..10 {
+        let x = f64::from(i) * 13.0;
+        let y = f64::from(i) * -7.0;
+        let expected = plain.get_raw(-y, x);
+        let actual = rotated.get_raw(x, y);
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "Rotated value {actual} should match {expected} at ({x}, {y})"
+        );
+    }
+
+    // The same rotation applies to 3D sampling
+    let expected = plain.get_raw_3d(-2.0, 1.0, 5.0);
+    let actual = rotated.get_raw_3d(1.0, 2.0, 5.0);
+    assert!((expected - actual).abs() < 1e-9);
+}
+
+#[test]
+fn noise_custom_transform_applies_to_fractal() {
+    use bevy::math::{DAffine3, DVec3};
+
+    let translated =
+        Noise::new(42).with_transform(DAffine3::from_translation(DVec3::new(250.0, -75.0, 0.0)));
+    let offset = Noise::new(42).with_axis_offset(DVec3::new(250.0, -75.0, 0.0));
+
+    for i in 0..10 {
+        let x = f64::from(i) * 3.0;
+        let a = translated.get_fractal(x, x, 4, 0.5, 2.0);
+        let b = offset.get_fractal(x, x, 4, 0.5, 2.0);
+        assert!(
+            (a - b).abs() < 1e-9,
+            "Transform and offset should agree at x={x}: {a} vs {b}"
+        );
+    }
+}
+
 #[test]
 fn fractal_noise_produces_valid_values() {
     let noise = Noise::new(42);
@@ -294,6 +394,60 @@ fn fractal_octaves_affect_detail() {
     // (though not guaranteed for every single point)
 }
 
+#[test]
+fn equalized_noise_is_approximately_uniform() {
+    let settings = CalibrationSettings::default().with_sampler(CalibrationSampler::Fractal {
+        octaves: 4,
+        persistence: 0.5,
+        lacunarity: 2.0,
+    });
+    let noise = Noise::new(42).calibrated(&settings);
+
+    // Sample a region different from the calibration grid
+    let mut below = 0;
+    let mut total = 0;
+    for x in 0..100 {
+        for y in 0..100 {
+            let value = noise.get_fractal_equalized(
+                f64::from(x) * 37.0 + 5000.0,
+                f64::from(y) * 37.0,
+                4,
+                0.5,
+                2.0,
+            );
+            assert!((0.0..=1.0).contains(&value));
+            if value < 0.3 {
+                below += 1;
+            }
+            total += 1;
+        }
+    }
+
+    let fraction = f64::from(below) / f64::from(total);
+    assert!(
+        (0.2..=0.4).contains(&fraction),
+        "Expected roughly 30% of samples below 0.3, got {fraction}"
+    );
+}
+
+#[test]
+fn calibrated_noise_spans_full_range() {
+    let noise = Noise::new(42).calibrated(&CalibrationSettings::default());
+
+    let mut min = f64::MAX;
+    let mut max = f64::MIN;
+    for x in 0..200 {
+        for y in 0..200 {
+            let value = noise.get_calibrated(f64::from(x) * 13.3, f64::from(y) * 13.3);
+            min = min.min(value);
+            max = max.max(value);
+        }
+    }
+
+    assert!(min < 0.1, "Calibrated minimum {min} should approach 0.0");
+    assert!(max > 0.9, "Calibrated maximum {max} should approach 1.0");
+}
+
 #[test]
 fn noise_reseed_changes_output() {
     let mut source = NoiseSource::new(12345);
@@ -377,6 +531,880 @@ fn noise_reflection_registered() {
     );
 }
 
+#[test]
+fn noise_stats_report_distribution() {
+    use bevy::math::{DVec2, DVec3, UVec2, UVec3};
+
+    let noise = Noise::new(42);
+
+    let area = noise.stats(
+        &StatsSettings::area(DVec2::ZERO, DVec2::splat(2048.0), UVec2::splat(64)).with_bins(10),
+    );
+    assert_eq!(area.count(), 64 * 64);
+    assert!((0.0..=1.0).contains(&area.min()));
+    assert!((0.0..=1.0).contains(&area.max()));
+    assert!(
+        (0.3..=0.7).contains(&area.mean()),
+        "Mean {} should be near 0.5",
+        area.mean()
+    );
+    assert_eq!(area.histogram().len(), 10);
+    assert_eq!(area.histogram().iter().sum::<u32>() as usize, area.count());
+    assert!(area.autocorrelation_length() > 0.0);
+
+    let volume = noise.stats(
+        &StatsSettings::volume(DVec3::ZERO, DVec3::splat(512.0), UVec3::splat(16))
+            .with_mode(SampleMode::Raw),
+    );
+    assert_eq!(volume.count(), 16 * 16 * 16);
+    assert!(volume.min() < 0.0 && volume.max() > 0.0);
+}
+
+#[test]
+fn noise_diagnostics_report_samples() {
+    use bevy::diagnostic::DiagnosticsStore;
+
+    let mut app = App::new();
+    app.add_plugins(NoisePlugin::seeded(42));
+    app.add_plugins(NoiseDiagnosticsPlugin);
+    app.add_systems(Update, |noise: Res<NoiseSource>| {
+        let terrain = noise.create(0x5445_5252);
+        for i in 0..100 {
+            let _ = terrain.get_normalized(f64::from(i), 0.0);
+        }
+    });
+
+    app.update();
+
+    let store = app.world().resource::<DiagnosticsStore>();
+    let samples = store
+        .get(&NoiseDiagnosticsPlugin::SAMPLES)
+        .and_then(bevy::diagnostic::Diagnostic::value)
+        .expect("Sample diagnostic should have a measurement");
+    // Apps report the samples of the whole process, so other tests may add
+    // samples concurrently
+    assert!(
+        samples >= 100.0,
+        "Expected at least 100 samples, got {samples}"
+    );
+    assert!(
+        store
+            .get(&NoiseDiagnosticsPlugin::EVALUATION_TIME)
+            .and_then(bevy::diagnostic::Diagnostic::value)
+            .is_some()
+    );
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn noise_to_image_formats() {
+    use bevy::math::{DVec2, UVec2};
+
+    let noise = Noise::new(42);
+    let region = SampleRegion::new(DVec2::ZERO, DVec2::splat(512.0));
+    let size = UVec2::new(16, 8);
+
+    for (format, bytes_per_pixel) in [
+        (NoiseImageFormat::R8, 1),
+        (NoiseImageFormat::R16, 2),
+        (NoiseImageFormat::R32F, 4),
+        (NoiseImageFormat::Rgba8, 4),
+    ] {
+        let image = noise.to_image(size, region, format);
+        assert_eq!(image.width(), 16);
+        assert_eq!(image.height(), 8);
+        assert_eq!(
+            image.data.as_ref().map(Vec::len),
+            Some(16 * 8 * bytes_per_pixel),
+            "Unexpected data size for {format:?}"
+        );
+    }
+
+    // R32F stores the sampled values unchanged
+    let image = noise.to_image(size, region, NoiseImageFormat::R32F);
+    let data = image.data.as_ref().unwrap();
+    let first = f32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    let expected = noise.get_normalized(16.0, 32.0) as f32;
+    assert!((first - expected).abs() < f32::EPSILON);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn noise_image_can_be_added_as_asset() {
+    use bevy::math::{DVec2, UVec2};
+
+    let mut app = App::new();
+    app.add_plugins((bevy::asset::AssetPlugin::default(), NoisePlugin::seeded(42)));
+    app.init_asset::<bevy::image::Image>();
+
+    let source = app.world().resource::<NoiseSource>();
+    let image = NoiseImage::new(
+        UVec2::splat(32),
+        SampleRegion::new(DVec2::ZERO, DVec2::splat(1024.0)),
+        NoiseImageFormat::Rgba8,
+    )
+    .with_ramp(ColorRamp::terrain())
+    .with_mipmaps(true)
+    .build(&source.create(0x5445_5252));
+
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<bevy::image::Image>>()
+        .add(image);
+    let images = app.world().resource::<Assets<bevy::image::Image>>();
+    let stored = images.get(&handle).expect("Image should be stored");
+    assert_eq!(stored.texture_descriptor.mip_level_count, 6);
+}
+
+#[cfg(feature = "mesh")]
+#[test]
+fn heightmap_chunks_share_edge_vertices() {
+    use bevy::math::{DVec2, UVec2};
+    use bevy::mesh::{Mesh, VertexAttributeValues};
+
+    let terrain = NoiseSource::new(42).create(0x5445_5252).with_scale(0.05);
+    let builder = HeightmapMeshBuilder::from_noise(&terrain, SampleMode::Normalized)
+        .with_resolution(UVec2::splat(8))
+        .with_height_scale(10.0);
+
+    let west = builder
+        .clone()
+        .with_region(SampleRegion::new(DVec2::ZERO, DVec2::splat(32.0)))
+        .build();
+    let east = builder
+        .with_region(SampleRegion::new(DVec2::new(32.0, 0.0), DVec2::splat(32.0)))
+        .build();
+
+    let attribute = |mesh: &Mesh, id| match mesh.attribute(id) {
+        Some(VertexAttributeValues::Float32x3(values)) => values.clone(),
+        _ => panic!("Missing attribute"),
+    };
+    let west_positions = attribute(&west, Mesh::ATTRIBUTE_POSITION);
+    let east_positions = attribute(&east, Mesh::ATTRIBUTE_POSITION);
+    let west_normals = attribute(&west, Mesh::ATTRIBUTE_NORMAL);
+    let east_normals = attribute(&east, Mesh::ATTRIBUTE_NORMAL);
+
+    // The west chunk's +X edge matches the east chunk's -X edge
+    for j in 0..=8 {
+        let west_index = j * 9 + 8;
+        let east_index = j * 9;
+        assert!((west_positions[west_index][1] - east_positions[east_index][1]).abs() < 1e-5);
+        assert!((west_positions[west_index][2] - east_positions[east_index][2]).abs() < 1e-5);
+        for axis in 0..3 {
+            assert!((west_normals[west_index][axis] - east_normals[east_index][axis]).abs() < 1e-4);
+        }
+    }
+}
+
+#[cfg(feature = "mesh")]
+#[test]
+fn heightmap_skirts_add_edge_vertices() {
+    use bevy::math::UVec2;
+
+    let without = HeightmapMeshBuilder::new(|x, z| (x + z) * 0.01)
+        .with_resolution(UVec2::new(4, 2))
+        .build();
+    let with = HeightmapMeshBuilder::new(|x, z| (x + z) * 0.01)
+        .with_resolution(UVec2::new(4, 2))
+        .with_skirt(1.0)
+        .build();
+
+    // 5x3 grid plus one skirt vertex per edge vertex on each of the 4 edges
+    assert_eq!(without.count_vertices(), 15);
+    assert_eq!(with.count_vertices(), 15 + 5 + 3 + 5 + 3);
+}
+
+#[cfg(feature = "mesh")]
+#[test]
+fn isosurface_chunks_are_seam_free() {
+    use bevy::math::{DVec3, UVec3, Vec3};
+
+    let caves = NoiseSource::new(42).create(0x4341_5645).with_scale(0.08);
+
+    // Triangles of a chunk in world space, with corners in a canonical order
+    let triangles = |builder: &IsosurfaceBuilder, origin: DVec3| {
+        let surface = builder.extract();
+        surface
+            .indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let mut corners = [0, 1, 2].map(|i| {
+                    Vec3::from_array(surface.positions[triangle[i] as usize]) + origin.as_vec3()
+                });
+                corners.sort_by(|a, b| a.to_array().partial_cmp(&b.to_array()).unwrap());
+                corners
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for method in [
+        IsosurfaceMethod::SurfaceNets,
+        IsosurfaceMethod::MarchingTetrahedra,
+    ] {
+        let builder = IsosurfaceBuilder::from_noise(&caves, SampleMode::Normalized)
+            .with_threshold(0.5)
+            .with_method(method);
+
+        let whole = builder
+            .clone()
+            .with_chunk(DVec3::new(1.0, 3.0, 5.0), DVec3::new(24.0, 12.0, 12.0))
+            .with_resolution(UVec3::new(12, 6, 6));
+        let expected = triangles(&whole, DVec3::new(1.0, 3.0, 5.0));
+        assert!(!expected.is_empty(), "{method:?} should find a surface");
+
+        // Two half chunks together produce the same triangles as the whole
+        let mut halves = Vec::new();
+        for origin in [DVec3::new(1.0, 3.0, 5.0), DVec3::new(13.0, 3.0, 5.0)] {
+            let half = builder
+                .clone()
+                .with_chunk(origin, DVec3::splat(12.0))
+                .with_resolution(UVec3::splat(6));
+            halves.extend(triangles(&half, origin));
+        }
+
+        assert_eq!(halves.len(), expected.len(), "{method:?} triangle count");
+        for triangle in &halves {
+            assert!(
+                expected.iter().any(|other| {
+                    triangle
+                        .iter()
+                        .zip(other)
+                        .all(|(a, b)| a.distance(*b) < 1e-3)
+                }),
+                "{method:?} triangle {triangle:?} has no match in the whole chunk"
+            );
+        }
+    }
+}
+
+#[test]
+fn contours_stitch_across_chunks() {
+    use bevy::math::{DVec2, UVec2};
+
+    let coast = NoiseSource::new(42).create(0x434F_4153).with_scale(0.05);
+    let builder = ContourBuilder::from_noise(&coast, SampleMode::Normalized).with_level(0.5);
+
+    let whole = builder
+        .clone()
+        .with_region(SampleRegion::new(DVec2::ZERO, DVec2::new(128.0, 64.0)))
+        .with_resolution(UVec2::new(64, 32))
+        .extract_level(0.5);
+
+    let mut pieces = Vec::new();
+    for x in [0.0, 64.0] {
+        pieces.extend(
+            builder
+                .clone()
+                .with_region(SampleRegion::new(DVec2::new(x, 0.0), DVec2::splat(64.0)))
+                .with_resolution(UVec2::splat(32))
+                .extract_level(0.5),
+        );
+    }
+    let stitched = Contour::stitch(pieces, 1e-9);
+
+    // Same contours with the same total length and enclosed area
+    assert!(!whole.is_empty());
+    assert_eq!(stitched.len(), whole.len());
+    let total = |contours: &[Contour], f: fn(&Contour) -> f64| contours.iter().map(f).sum::<f64>();
+    assert!((total(&stitched, Contour::length) - total(&whole, Contour::length)).abs() < 1e-6);
+    assert!(
+        (total(&stitched, Contour::signed_area) - total(&whole, Contour::signed_area)).abs() < 1e-6
+    );
+    assert_eq!(
+        stitched.iter().filter(|c| c.closed).count(),
+        whole.iter().filter(|c| c.closed).count()
+    );
+}
+
+#[test]
+fn scatter_is_stable_across_chunks() {
+    use bevy::math::DVec2;
+
+    let source = NoiseSource::new(42);
+    let density = source.create(0x4445_4E53).with_scale(0.05);
+    let methods = [
+        ScatterMethod::Poisson { radius: 3.0 },
+        ScatterMethod::JitteredGrid {
+            spacing: 4.0,
+            jitter: 0.8,
+        },
+        ScatterMethod::density_noise(&density, SampleMode::Normalized, 2.0, 6.0),
+    ];
+
+    for method in methods {
+        let scatter = source.create_scatter(0x5452_4545, method);
+        let sorted = |mut points: Vec<DVec2>| {
+            points.sort_by(|a, b| a.to_array().partial_cmp(&b.to_array()).unwrap());
+            points
+        };
+        let whole = sorted(scatter.points(SampleRegion::new(DVec2::ZERO, DVec2::new(100.0, 50.0))));
+
+        // Chunks generated in any order partition the same points
+        let mut chunks = Vec::new();
+        for x in [50.0, 0.0] {
+            chunks
+                .extend(scatter.points(SampleRegion::new(DVec2::new(x, 0.0), DVec2::splat(50.0))));
+        }
+        assert!(!whole.is_empty());
+        assert_eq!(sorted(chunks), whole, "{:?}", scatter.method());
+    }
+}
+
+#[test]
+fn placements_are_stable_when_chunks_reload() {
+    use bevy::math::DVec2;
+
+    let source = NoiseSource::new(42);
+    let height = source.create(0x4845_4947).with_scale(0.02);
+    let rules = PlacementRules::new()
+        .with_rule(
+            PlacementRule::new(0x5452_4545, ScatterMethod::Poisson { radius: 3.0 })
+                .with_mask(PlacementMask::band(
+                    &height,
+                    SampleMode::Normalized,
+                    0.4,
+                    1.0,
+                ))
+                .with_ground(&height, SampleMode::Normalized, 30.0)
+                .with_scale_range(0.8, 1.2),
+        )
+        .with_rule(
+            PlacementRule::new(0x524F_434B, ScatterMethod::Poisson { radius: 6.0 })
+                .with_density(0.5),
+        );
+
+    let chunk = SampleRegion::new(DVec2::new(64.0, -64.0), DVec2::splat(64.0));
+    let first = rules.place(&source, chunk);
+    let _ = rules.place(&source, SampleRegion::new(DVec2::ZERO, DVec2::splat(64.0)));
+    let reloaded = rules.place(&source, chunk);
+
+    assert!(!first.is_empty());
+    assert_eq!(first, reloaded);
+    for placement in &first {
+        if placement.key == 0x5452_4545 {
+            let value = height.get_normalized(placement.position.x, placement.position.z);
+            assert!(value >= 0.4);
+            assert!((placement.position.y - value * 30.0).abs() < 1e-9);
+        }
+    }
+
+    // A different world seed produces different props
+    let other = rules.place(&NoiseSource::new(43), chunk);
+    assert_ne!(first, other);
+}
+
+#[test]
+fn placements_are_stable_when_regions_split_into_chunks() {
+    use bevy::math::DVec2;
+
+    let source = NoiseSource::new(42);
+    let height = source.create(0x4845_4947).with_scale(0.02);
+    let rules = PlacementRules::new()
+        .with_rule(
+            PlacementRule::new(0x5452_4545, ScatterMethod::Poisson { radius: 3.0 })
+                .with_mask(PlacementMask::slope(
+                    &height,
+                    SampleMode::Normalized,
+                    30.0,
+                    0.0,
+                    0.6,
+                ))
+                .with_ground(&height, SampleMode::Normalized, 30.0)
+                .with_random_yaw(true)
+                .with_scale_range(0.8, 1.2),
+        )
+        .with_rule(
+            PlacementRule::new(
+                0x4752_5353,
+                ScatterMethod::JitteredGrid {
+                    spacing: 4.0,
+                    jitter: 1.0,
+                },
+            )
+            .with_density(0.5),
+        );
+
+    let sorted = |mut placements: Vec<Placement>| {
+        placements.sort_by(|a, b| {
+            (a.key, a.position.x, a.position.z)
+                .partial_cmp(&(b.key, b.position.x, b.position.z))
+                .unwrap()
+        });
+        placements
+    };
+
+    // One region, and the same region as four quadrant chunks
+    let origin = DVec2::new(-48.0, 16.0);
+    let whole = sorted(rules.place(&source, SampleRegion::new(origin, DVec2::splat(96.0))));
+    let mut chunks = Vec::new();
+    for quadrant in [DVec2::ZERO, DVec2::X, DVec2::Y, DVec2::ONE] {
+        let chunk = SampleRegion::new(origin + quadrant * 48.0, DVec2::splat(48.0));
+        chunks.extend(rules.place(&source, chunk));
+    }
+
+    assert!(whole.iter().any(|p| p.key == 0x5452_4545));
+    assert!(whole.iter().any(|p| p.key == 0x4752_5353));
+    assert_eq!(whole, sorted(chunks));
+}
+
+#[test]
+fn biome_map_rasterizes_whittaker_table() {
+    use bevy::math::{DVec2, UVec2};
+
+    let source = NoiseSource::new(42);
+    let temperature = source.create(0x5445_4D50).with_scale(0.01);
+    let moisture = source.create(0x4D4F_4953).with_scale(0.013);
+    let biomes = BiomeMap::new()
+        .with_layer("temperature", &temperature, SampleMode::Normalized)
+        .with_layer("moisture", &moisture, SampleMode::Normalized)
+        .with_biome(
+            Biome::new(0)
+                .with_range("temperature", 0.5, 1.0)
+                .with_range("moisture", 0.0, 0.5),
+        )
+        .with_biome(
+            Biome::new(1)
+                .with_range("temperature", 0.5, 1.0)
+                .with_range("moisture", 0.5, 1.0),
+        )
+        .with_biome(Biome::new(2).with_range("temperature", 0.0, 0.5));
+
+    let region = SampleRegion::new(DVec2::ZERO, DVec2::splat(256.0));
+    let grid = biomes.rasterize(region, UVec2::splat(32));
+
+    assert_eq!(grid.ids().len(), 32 * 32);
+    for y in 0..32 {
+        for x in 0..32 {
+            let cell = UVec2::new(x, y);
+            let p = region.cell_center(cell, UVec2::splat(32));
+            let (t, m) = (
+                temperature.get_normalized(p.x, p.y),
+                moisture.get_normalized(p.x, p.y),
+            );
+            let expected = match (t >= 0.5, m >= 0.5) {
+                (false, _) => 2,
+                (true, false) => 0,
+                (true, true) => 1,
+            };
+            if (t - 0.5).abs() > 1e-9 && (m - 0.5).abs() > 1e-9 {
+                assert_eq!(grid.get(cell), Some(expected));
+            }
+        }
+    }
+    let mut seen: Vec<u32> = grid.ids().iter().flatten().copied().collect();
+    seen.sort_unstable();
+    seen.dedup();
+    assert_eq!(seen, vec![0, 1, 2]);
+
+    // Blend weights change smoothly across a border
+    let blended = biomes.clone().with_blend_width(0.1);
+    let sample = blended.sample(128.0, 128.0).unwrap();
+    assert_eq!(Some(sample.id), biomes.biome_at(128.0, 128.0));
+    assert!((sample.weights.iter().map(|(_, w)| w).sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn noise_signal_shakes_transform_and_settles() {
+    use std::time::Duration;
+
+    let mut app = App::new();
+    app.add_plugins(NoisePlugin::seeded(42))
+        .insert_resource(Time::<()>::default());
+
+    let noise = app
+        .world()
+        .resource::<NoiseSource>()
+        .create(0x5348_4B45)
+        .with_scale(1.0);
+    let camera = app
+        .world_mut()
+        .spawn((
+            Transform::from_xyz(0.0, 5.0, 10.0),
+            NoiseSignal::new(noise)
+                .with_frequency(10.0)
+                .with_translation(Vec3::splat(0.5))
+                .with_rotation(Vec3::splat(0.05))
+                .with_trauma(1.0)
+                .with_decay(2.0),
+        ))
+        .id();
+
+    let step = |app: &mut App| {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(100));
+        app.update();
+    };
+
+    step(&mut app);
+    let shaken = *app.world().get::<Transform>(camera).unwrap();
+    assert!(shaken.translation.distance(Vec3::new(0.0, 5.0, 10.0)) > 1e-4);
+
+    // Trauma decays to zero after half a second and the transform returns home
+    for _ in 0..10 {
+        step(&mut app);
+    }
+    let settled = *app.world().get::<Transform>(camera).unwrap();
+    assert!(settled.translation.distance(Vec3::new(0.0, 5.0, 10.0)) < 1e-5);
+    assert!(settled.rotation.abs_diff_eq(Quat::IDENTITY, 1e-5));
+}
+
+#[test]
+fn noise_signal_offsets_transform_set_by_other_systems() {
+    use std::time::Duration;
+
+    let home = Vec3::new(0.0, 5.0, 10.0);
+    let mut app = App::new();
+    app.add_plugins(NoisePlugin::seeded(42))
+        .insert_resource(Time::<()>::default())
+        .add_systems(
+            Update,
+            move |mut cameras: Query<&mut Transform, With<NoiseSignal>>| {
+                for mut transform in &mut cameras {
+                    *transform = Transform::from_translation(home);
+                }
+            },
+        );
+
+    let noise = app
+        .world()
+        .resource::<NoiseSource>()
+        .create(0x5348_4B45)
+        .with_scale(1.0);
+    let camera = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            NoiseSignal::new(noise)
+                .with_frequency(10.0)
+                .with_translation(Vec3::splat(0.5))
+                .with_rotation(Vec3::splat(0.05)),
+        ))
+        .id();
+
+    for _ in 0..5 {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(100));
+        app.update();
+
+        // The transform is the follow pose plus exactly the current offset
+        let transform = *app.world().get::<Transform>(camera).unwrap();
+        let (translation, rotation) = app.world().get::<NoiseSignal>(camera).unwrap().offset();
+        assert!(translation.length() > 1e-4);
+        assert!(transform.translation.distance(home + translation) < 1e-5);
+        assert!(transform.rotation.abs_diff_eq(rotation, 1e-5));
+    }
+}
+
+#[test]
+fn noise_signal_offset_is_removed_with_component() {
+    use std::time::Duration;
+
+    let home = Transform::from_xyz(0.0, 5.0, 10.0);
+    let mut app = App::new();
+    app.add_plugins(NoisePlugin::seeded(42))
+        .insert_resource(Time::<()>::default());
+
+    let noise = app
+        .world()
+        .resource::<NoiseSource>()
+        .create(7)
+        .with_scale(1.0);
+    let camera = app
+        .world_mut()
+        .spawn((
+            home,
+            NoiseSignal::new(noise)
+                .with_frequency(10.0)
+                .with_translation(Vec3::splat(0.5))
+                .with_rotation(Vec3::splat(0.05)),
+        ))
+        .id();
+
+    for _ in 0..3 {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(100));
+        app.update();
+    }
+    let shaken = *app.world().get::<Transform>(camera).unwrap();
+    assert!(shaken.translation.distance(home.translation) > 1e-4);
+
+    // Removed mid-shake, between PostUpdate and the next PreUpdate
+    app.world_mut().entity_mut(camera).remove::<NoiseSignal>();
+    let restored = *app.world().get::<Transform>(camera).unwrap();
+    assert!(restored.translation.distance(home.translation) < 1e-5);
+    assert!(restored.rotation.abs_diff_eq(home.rotation, 1e-5));
+
+    app.update();
+    assert_eq!(*app.world().get::<Transform>(camera).unwrap(), restored);
+}
+
+#[test]
+fn flow_field_matches_curl_noise() {
+    use bevy::math::{DVec2, UVec2, Vec2};
+
+    let wind = CurlNoise2D::new(NoiseSource::new(42).create(0x5749_4E44).with_scale(0.02))
+        .with_mode(SampleMode::Fractal {
+            octaves: 3,
+            persistence: 0.5,
+            lacunarity: 2.0,
+        })
+        .with_strength(10.0);
+    let region = SampleRegion::new(DVec2::new(-64.0, -64.0), DVec2::splat(128.0));
+    let field = wind.bake(region, UVec2::splat(32));
+
+    for (x, y) in [(0, 0), (5, 17), (31, 31)] {
+        let cell = UVec2::new(x, y);
+        let center = region.cell_center(cell, UVec2::splat(32));
+        let expected = wind.velocity(center.x, center.y).as_vec2();
+        assert!(field.get(cell).distance(expected) < 1e-5);
+        assert!(field.sample(center.as_vec2()).distance(expected) < 1e-4);
+    }
+    assert!(field.vectors().iter().any(|v| v.length() > 0.1));
+    assert!(field.sample(Vec2::new(1e6, -1e6)).is_finite());
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn time_noise_updates_image_each_frame() {
+    use bevy::math::{DVec2, UVec2};
+    use std::time::Duration;
+
+    let mut app = App::new();
+    app.add_plugins(NoisePlugin::seeded(42))
+        .insert_resource(Time::<()>::default())
+        .init_resource::<Assets<Image>>();
+
+    let settings = NoiseImage::new(
+        UVec2::splat(16),
+        SampleRegion::new(DVec2::ZERO, DVec2::splat(16.0)),
+        NoiseImageFormat::R32F,
+    );
+    let noise = TimeNoise::new(Noise::new(7).with_scale(0.2))
+        .with_speed(2.0)
+        .with_loop(1.0);
+    let initial = settings.build_with(|x, y| noise.get_normalized(x, y));
+    let mut configured = initial.clone();
+    configured.sampler = bevy::image::ImageSampler::nearest();
+    configured.texture_descriptor.label = Some("water");
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<Image>>()
+        .add(configured);
+    let entity = app
+        .world_mut()
+        .spawn((noise, TimeNoiseImage::new(handle.clone(), settings)))
+        .id();
+
+    let step = |app: &mut App, millis| {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(millis));
+        app.update();
+    };
+
+    step(&mut app, 250);
+    let time = app.world().get::<TimeNoise>(entity).unwrap().time();
+    assert!((time - 0.25).abs() < 1e-9);
+    let images = app.world().resource::<Assets<Image>>();
+    let frame = images.get(&handle).unwrap();
+    assert_ne!(frame.data, initial.data);
+    // Pixels are written in place, keeping the sampler and descriptor
+    assert!(matches!(
+        frame.sampler,
+        bevy::image::ImageSampler::Descriptor(_)
+    ));
+    assert_eq!(frame.texture_descriptor.label, Some("water"));
+    assert_eq!(
+        frame.texture_descriptor.size,
+        initial.texture_descriptor.size
+    );
+
+    // One full period later the looping animation is back where it started
+    for _ in 0..3 {
+        step(&mut app, 250);
+    }
+    let looped = app
+        .world()
+        .resource::<Assets<Image>>()
+        .get(&handle)
+        .unwrap()
+        .data
+        .clone();
+    let (a, b) = (looped.unwrap(), initial.data.unwrap());
+    for (a, b) in a.chunks_exact(4).zip(b.chunks_exact(4)) {
+        let (a, b) = (
+            f32::from_le_bytes(a.try_into().unwrap()),
+            f32::from_le_bytes(b.try_into().unwrap()),
+        );
+        assert!((a - b).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn tile_values_are_reproducible_across_sources() {
+    use bevy::math::IVec2;
+
+    const ORE: u32 = 0x4F52_4553;
+    const GRASS: u32 = 0x4752_4153;
+
+    let a = NoiseSource::new(99);
+    let b = NoiseSource::new(99);
+    let other = NoiseSource::new(100);
+
+    let mut same_as_other = 0;
+    for y in -8..8 {
+        for x in -8..8 {
+            let tile = IVec2::new(x, y);
+            assert_eq!(a.hash_tile(tile, ORE), b.hash_tile(tile, ORE));
+            assert_eq!(a.random_f64_at(tile, GRASS), b.random_f64_at(tile, GRASS));
+            assert_eq!(
+                a.create(ORE).sample_tile(tile),
+                b.create(ORE).sample_tile(tile)
+            );
+
+            let density = a.random_range_at(tile, GRASS, 2.0, 5.0);
+            assert!((2.0..5.0).contains(&density));
+            if a.hash_tile(tile, ORE) == other.hash_tile(tile, ORE) {
+                same_as_other += 1;
+            }
+        }
+    }
+    assert_eq!(same_as_other, 0);
+}
+
+#[test]
+fn noise_layers_rebuild_after_reflected_edits() {
+    use bevy::reflect::{FromReflect, GetPath};
+
+    let mut app = App::new();
+    app.add_plugins(NoisePlugin::seeded(7));
+
+    let source = NoiseSource::new(7);
+    let layers = NoiseLayers::new(&source, 42)
+        .with_layer(NoiseLayer::new("continents").with_scale(0.01))
+        .with_layer(
+            NoiseLayer::new("mountains")
+                .with_scale(0.05)
+                .with_weight(0.5),
+        );
+    let original = layers.sample(10.5, 20.5);
+    let planet = app.world_mut().spawn(layers).id();
+
+    // Reflection round trip, as when saving and loading a scene
+    let mut stored = app.world_mut().get_mut::<NoiseLayers>(planet).unwrap();
+    let copy = NoiseLayers::from_reflect(stored.as_reflect()).unwrap();
+    assert_eq!(copy.sample(10.5, 20.5), original);
+
+    // Inspector-style edits of a generator setting and a blend setting
+    *stored
+        .reflect_path_mut("layers[0].scale")
+        .unwrap()
+        .try_downcast_mut::<f64>()
+        .unwrap() = 0.02;
+    *stored
+        .reflect_path_mut("layers[1].weight")
+        .unwrap()
+        .try_downcast_mut::<f64>()
+        .unwrap() = 0.0;
+    app.update();
+
+    let stored = app.world().get::<NoiseLayers>(planet).unwrap();
+    assert_eq!(stored.layers()[1].weight(), 0.0);
+    assert!(
+        stored.noise("continents").is_some(),
+        "generators should be rebuilt by NoisePlugin"
+    );
+    assert_ne!(stored.sample(10.5, 20.5), original);
+    assert_eq!(
+        stored.sample(10.5, 20.5),
+        NoiseLayers::new(&source, 42)
+            .with_layer(NoiseLayer::new("continents").with_scale(0.02))
+            .sample(10.5, 20.5)
+    );
+    assert!(
+        app.world()
+            .resource::<AppTypeRegistry>()
+            .read()
+            .contains(std::any::TypeId::of::<NoiseLayers>())
+    );
+}
+
+#[test]
+fn noise_layers_with_unsupported_version_fall_back() {
+    use bevy::reflect::GetPath;
+
+    let mut app = App::new();
+    app.add_plugins(NoisePlugin::seeded(7));
+
+    let source = NoiseSource::new(7);
+    let mut layers =
+        NoiseLayers::new(&source, 42).with_layer(NoiseLayer::new("continents").with_scale(0.01));
+    let expected = layers.sample(10.5, 20.5);
+
+    // As loaded from a scene saved by a newer release
+    *layers
+        .reflect_path_mut("output_version")
+        .unwrap()
+        .try_downcast_mut::<u32>()
+        .unwrap() = 99;
+    let planet = app.world_mut().spawn(layers).id();
+    app.update();
+    app.update();
+
+    let stored = app.world().get::<NoiseLayers>(planet).unwrap();
+    assert!(stored.noise("continents").is_some());
+    assert_eq!(stored.sample(10.5, 20.5), expected);
+}
+
+#[cfg(feature = "preview")]
+#[test]
+fn noise_preview_rerenders_after_reflected_edits() {
+    use bevy::reflect::GetPath;
+
+    let mut app = App::new();
+    app.add_plugins((NoisePlugin::seeded(11), NoisePreviewPlugin))
+        .init_resource::<Assets<Image>>();
+
+    let (image, histogram) = {
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        (images.add(Image::default()), images.add(Image::default()))
+    };
+    let recipe = NoiseRecipe::new(0x5052_4556).with_scale(0.05);
+    let preview = NoisePreview::new(recipe, image.clone())
+        .with_resolution(16)
+        .with_extent(64.0)
+        .with_histogram_bins(4)
+        .with_histogram_image(histogram.clone());
+    let expected = preview.render(&NoiseSource::new(11));
+    let entity = app.world_mut().spawn(preview).id();
+    app.update();
+
+    let pixels = |app: &App| {
+        let images = app.world().resource::<Assets<Image>>();
+        images.get(&image).unwrap().data.clone().unwrap()
+    };
+    let first = pixels(&app);
+    assert_eq!(Some(&first), expected.image.data.as_ref());
+    let stored = app.world().get::<NoisePreview>(entity).unwrap();
+    assert_eq!(stored.histogram(), expected.histogram.as_slice());
+    assert_eq!(stored.histogram().iter().sum::<u32>(), 16 * 16);
+    let images = app.world().resource::<Assets<Image>>();
+    assert_eq!(images.get(&histogram).unwrap().width(), 4);
+
+    // Inspector-style edit of the recipe
+    let mut stored = app.world_mut().get_mut::<NoisePreview>(entity).unwrap();
+    *stored
+        .reflect_path_mut("recipe.scale")
+        .unwrap()
+        .try_downcast_mut::<f64>()
+        .unwrap() = 0.2;
+    app.update();
+    assert_ne!(pixels(&app), first);
+}
+
 // --- Tests for GlobalRng initialization path ---
 
 #[test]